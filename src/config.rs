@@ -1,17 +1,57 @@
+use crate::modules::censys::CensysQuery;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::Path;
 
+/// Transport used to talk to the `--server` name servers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum DnsProtocol {
+    Udp,
+    Tcp,
+    /// DNS-over-TLS
+    Tls,
+    /// DNS-over-HTTPS
+    Https,
+}
+
+impl DnsProtocol {
+    pub fn default_port(&self) -> u16 {
+        match self {
+            DnsProtocol::Udp | DnsProtocol::Tcp => 53,
+            DnsProtocol::Tls => 853,
+            DnsProtocol::Https => 443,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
     pub shodan_api_key: Option<String>,
     pub spyse_api_key: Option<String>,
     pub censys_api_id: Option<String>,
     pub censys_api_secret: Option<String>,
+    pub virustotal_api_key: Option<String>,
     pub proxy: Option<String>,
-    pub dns_server: Option<String>,
+    pub timeout: u64,
+    pub insecure: bool,
+    pub user_agents: Option<String>,
+    pub delay_min: u64,
+    pub delay_max: u64,
+    pub dns_servers: Vec<String>,
+    pub dns_protocol: DnsProtocol,
+    pub dns_tls_name: Option<String>,
+    pub dns_timeout: u64,
+    pub dns_retries: usize,
+    pub ip_ranges: Vec<String>,
+    pub wordlist: Option<String>,
+    pub brute_rate: u32,
+    pub recursive: bool,
+    pub keyservers: Vec<String>,
+    pub censys_query: CensysQuery,
     pub limit: u32,
+    pub concurrency: usize,
     pub debug: bool,
     pub verbose: bool,
 }
@@ -23,9 +63,36 @@ impl Config {
             spyse_api_key: args.spyse_key.clone(),
             censys_api_id: args.censys_api_id.clone(),
             censys_api_secret: args.censys_api_secret.clone(),
+            virustotal_api_key: args.virustotal_key.clone(),
             proxy: args.proxy.clone(),
-            dns_server: args.server.clone(),
+            timeout: args.timeout,
+            insecure: args.insecure,
+            user_agents: args.user_agents.clone(),
+            delay_min: args.delay_min,
+            delay_max: args.delay_max,
+            dns_servers: args.server.clone(),
+            dns_protocol: args.dns_protocol,
+            dns_tls_name: args.dns_tls_name.clone(),
+            dns_timeout: args.dns_timeout,
+            dns_retries: args.dns_retries,
+            ip_ranges: args.range.clone(),
+            wordlist: args.wordlist.clone(),
+            brute_rate: args.rate,
+            recursive: args.recursive,
+            keyservers: args.keyserver.clone(),
+            censys_query: CensysQuery {
+                tags: args.tags.clone(),
+                asn: args.asn,
+                cert_org: args.cert_org.clone(),
+                cert_issuer: args.cert_issuer.clone(),
+                cert_host: args.cert_host.clone(),
+                country: args.country.clone(),
+                http_server: args.http_server.clone(),
+                html_title: args.html_title.clone(),
+                html_body: args.html_body.clone(),
+            },
             limit: args.limit,
+            concurrency: args.concurrency.max(1),
             debug: args.debug,
             verbose: args.verbose,
         };
@@ -44,25 +111,20 @@ impl Config {
                 let parts: Vec<&str> = line.split('=').collect();
                 if parts.len() == 2 {
                     match parts[0].trim() {
-                        "SHODAN_API_KEY" => {
-                            if self.shodan_api_key.is_none() {
-                                self.shodan_api_key = Some(parts[1].trim().to_string());
-                            }
+                        "SHODAN_API_KEY" if self.shodan_api_key.is_none() => {
+                            self.shodan_api_key = Some(parts[1].trim().to_string());
                         }
-                        "SPYSE_API_KEY" => {
-                            if self.spyse_api_key.is_none() {
-                                self.spyse_api_key = Some(parts[1].trim().to_string());
-                            }
+                        "SPYSE_API_KEY" if self.spyse_api_key.is_none() => {
+                            self.spyse_api_key = Some(parts[1].trim().to_string());
                         }
-                        "CENSYS_API_ID" => {
-                            if self.censys_api_id.is_none() {
-                                self.censys_api_id = Some(parts[1].trim().to_string());
-                            }
+                        "CENSYS_API_ID" if self.censys_api_id.is_none() => {
+                            self.censys_api_id = Some(parts[1].trim().to_string());
                         }
-                        "CENSYS_API_SECRET" => {
-                            if self.censys_api_secret.is_none() {
-                                self.censys_api_secret = Some(parts[1].trim().to_string());
-                            }
+                        "CENSYS_API_SECRET" if self.censys_api_secret.is_none() => {
+                            self.censys_api_secret = Some(parts[1].trim().to_string());
+                        }
+                        "VIRUSTOTAL_API_KEY" if self.virustotal_api_key.is_none() => {
+                            self.virustotal_api_key = Some(parts[1].trim().to_string());
                         }
                         _ => {}
                     }
@@ -87,6 +149,9 @@ impl Config {
         if let Some(secret) = &self.censys_api_secret {
             contents.push_str(&format!("CENSYS_API_SECRET={}\n", secret));
         }
+        if let Some(key) = &self.virustotal_api_key {
+            contents.push_str(&format!("VIRUSTOTAL_API_KEY={}\n", key));
+        }
 
         fs::write("api_keys.txt", contents)?;
         Ok(())
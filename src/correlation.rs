@@ -0,0 +1,294 @@
+use crate::findings::Finding;
+use crate::modules::ModuleResult;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::net::IpAddr;
+
+/// Names of the modules that reported a fact.
+pub type Sources = BTreeSet<String>;
+
+/// Deduplicated view of every module result, keyed by host and IP address.
+#[derive(Debug, Default, Serialize)]
+pub struct Inventory {
+    pub domain: String,
+    pub hosts: BTreeMap<String, Host>,
+    pub ips: BTreeMap<IpAddr, Address>,
+    pub emails: BTreeMap<String, Sources>,
+    pub organizations: BTreeMap<String, Sources>,
+    pub asns: BTreeMap<u32, Sources>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Host {
+    pub sources: Sources,
+    /// Addresses the host resolves to, with the sources that linked them.
+    pub ips: BTreeMap<IpAddr, Sources>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Address {
+    pub sources: Sources,
+    pub ports: BTreeMap<u16, Service>,
+    pub operating_systems: BTreeMap<String, Sources>,
+    pub vulnerabilities: BTreeMap<String, Sources>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Service {
+    pub sources: Sources,
+    pub transports: BTreeSet<String>,
+    pub names: BTreeSet<String>,
+}
+
+impl Inventory {
+    /// Hostnames that belong to the target domain, excluding the domain itself.
+    pub fn subdomains(&self) -> Vec<&str> {
+        let suffix = format!(".{}", self.domain);
+        self.hosts
+            .keys()
+            .filter(|host| host.ends_with(&suffix))
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// IP addresses that no host has been linked to.
+    pub fn unattributed_ips(&self) -> Vec<IpAddr> {
+        let attributed: BTreeSet<&IpAddr> =
+            self.hosts.values().flat_map(|host| host.ips.keys()).collect();
+        self.ips
+            .keys()
+            .filter(|ip| !attributed.contains(ip))
+            .copied()
+            .collect()
+    }
+
+    fn add_host(&mut self, hostname: &str, source: &str) -> Option<String> {
+        let hostname = normalize_hostname(hostname)?;
+        self.hosts
+            .entry(hostname.clone())
+            .or_default()
+            .sources
+            .insert(source.to_string());
+        Some(hostname)
+    }
+
+    fn add_ip(&mut self, ip: IpAddr, source: &str) -> &mut Address {
+        let address = self.ips.entry(ip).or_default();
+        address.sources.insert(source.to_string());
+        address
+    }
+
+    fn link(&mut self, hostname: &str, ip: IpAddr, source: &str) {
+        self.add_ip(ip, source);
+        if let Some(hostname) = self.add_host(hostname, source) {
+            if let Some(host) = self.hosts.get_mut(&hostname) {
+                host.ips
+                    .entry(ip)
+                    .or_default()
+                    .insert(source.to_string());
+            }
+        }
+    }
+
+    fn add_finding(&mut self, finding: &Finding, source: &str) {
+        match finding {
+            Finding::Hostname { name } => {
+                self.add_host(name, source);
+            }
+            Finding::IpAddress { address } => {
+                self.add_ip(*address, source);
+            }
+            Finding::Email { address } | Finding::PgpKey { email: address, .. } => {
+                self.emails
+                    .entry(address.to_lowercase())
+                    .or_default()
+                    .insert(source.to_string());
+            }
+            Finding::OpenPort {
+                ip,
+                port,
+                transport,
+                service,
+            } => {
+                let entry = self.add_ip(*ip, source).ports.entry(*port).or_default();
+                entry.sources.insert(source.to_string());
+                entry.transports.extend(transport.clone());
+                entry.names.extend(service.clone());
+            }
+            Finding::DnsRecord {
+                name,
+                record_type,
+                value,
+                ..
+            } => match record_type.as_str() {
+                "A" | "AAAA" => {
+                    if let Ok(ip) = value.parse() {
+                        self.link(name, ip, source);
+                    }
+                }
+                "PTR" => {
+                    if let Ok(ip) = name.parse() {
+                        self.link(value, ip, source);
+                    }
+                }
+                "CNAME" | "MX" | "NS" => {
+                    self.add_host(name, source);
+                    // MX values carry their preference ("10 mail.example.com")
+                    if let Some(target) = value.split_whitespace().last() {
+                        self.add_host(target, source);
+                    }
+                }
+                _ => {}
+            },
+            Finding::Certificate { subject, .. } => {
+                self.add_host(subject, source);
+            }
+            Finding::Url { url } => {
+                if let Some(host) = reqwest::Url::parse(url)
+                    .ok()
+                    .and_then(|url| url.host_str().map(String::from))
+                {
+                    self.add_host(&host, source);
+                }
+            }
+            Finding::Organization { name } => {
+                self.organizations
+                    .entry(name.clone())
+                    .or_default()
+                    .insert(source.to_string());
+            }
+            Finding::Asn { number, .. } => {
+                self.asns
+                    .entry(*number)
+                    .or_default()
+                    .insert(source.to_string());
+            }
+            Finding::OperatingSystem { ip, name } => {
+                self.add_ip(*ip, source)
+                    .operating_systems
+                    .entry(name.clone())
+                    .or_default()
+                    .insert(source.to_string());
+            }
+            Finding::Vulnerability { ip, id } => {
+                self.add_ip(*ip, source)
+                    .vulnerabilities
+                    .entry(id.clone())
+                    .or_default()
+                    .insert(source.to_string());
+            }
+            Finding::VirtualHost { ip, hostname }
+            | Finding::Resolution { hostname, ip }
+            | Finding::ZoneTransfer {
+                nameserver: hostname,
+                ip,
+                ..
+            } => {
+                self.link(hostname, *ip, source);
+            }
+            Finding::WhoisField { .. }
+            | Finding::SocialProfile { .. }
+            | Finding::Facet { .. } => {}
+        }
+    }
+}
+
+/// Merges the findings of every module result into a single inventory.
+pub fn correlate(domain: &str, results: &[ModuleResult]) -> Inventory {
+    let mut inventory = Inventory {
+        domain: domain.to_lowercase(),
+        ..Default::default()
+    };
+
+    for result in results {
+        for finding in &result.findings {
+            inventory.add_finding(finding, &result.source);
+        }
+    }
+
+    inventory
+}
+
+fn normalize_hostname(hostname: &str) -> Option<String> {
+    let hostname = hostname
+        .trim()
+        .trim_start_matches("*.")
+        .trim_end_matches('.')
+        .to_lowercase();
+    if hostname.is_empty() || hostname.contains(char::is_whitespace) {
+        None
+    } else {
+        Some(hostname)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(name: &str, record_type: &str, value: &str) -> Finding {
+        Finding::DnsRecord {
+            name: name.to_string(),
+            record_type: record_type.to_string(),
+            value: value.to_string(),
+            ttl: None,
+        }
+    }
+
+    fn sources(names: &[&str]) -> Sources {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn merges_overlapping_findings_of_several_modules() {
+        let www: IpAddr = "192.0.2.80".parse().unwrap();
+        let stray: IpAddr = "198.51.100.7".parse().unwrap();
+        let results = [
+            ModuleResult {
+                source: "DNS".to_string(),
+                findings: vec![
+                    record("www.example.com.", "A", "192.0.2.80"),
+                    record("example.com", "MX", "10 Mail.Example.com."),
+                    Finding::Email {
+                        address: "Admin@example.com".to_string(),
+                    },
+                ],
+                metadata: None,
+            },
+            ModuleResult {
+                source: "Shodan".to_string(),
+                findings: vec![
+                    Finding::hostname("*.WWW.example.com"),
+                    record("192.0.2.80", "PTR", "www.example.com."),
+                    Finding::OpenPort {
+                        ip: www,
+                        port: 443,
+                        transport: Some("tcp".to_string()),
+                        service: Some("https".to_string()),
+                    },
+                    Finding::IpAddress { address: stray },
+                    Finding::Email {
+                        address: "admin@example.com".to_string(),
+                    },
+                ],
+                metadata: None,
+            },
+        ];
+
+        let inventory = correlate("Example.com", &results);
+
+        assert_eq!(inventory.subdomains(), ["mail.example.com", "www.example.com"]);
+        let host = &inventory.hosts["www.example.com"];
+        assert_eq!(host.sources, sources(&["DNS", "Shodan"]));
+        assert_eq!(host.ips[&www], sources(&["DNS", "Shodan"]));
+        assert_eq!(inventory.hosts["mail.example.com"].sources, sources(&["DNS"]));
+
+        let address = &inventory.ips[&www];
+        assert_eq!(address.sources, sources(&["DNS", "Shodan"]));
+        assert_eq!(address.ports[&443].sources, sources(&["Shodan"]));
+        assert_eq!(inventory.unattributed_ips(), [stray]);
+
+        assert_eq!(inventory.emails.len(), 1);
+        assert_eq!(inventory.emails["admin@example.com"], sources(&["DNS", "Shodan"]));
+    }
+}
@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::IpAddr;
+
+/// A single structured fact reported by a module.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Finding {
+    Hostname {
+        name: String,
+    },
+    IpAddress {
+        address: IpAddr,
+    },
+    Email {
+        address: String,
+    },
+    OpenPort {
+        ip: IpAddr,
+        port: u16,
+        transport: Option<String>,
+        service: Option<String>,
+    },
+    DnsRecord {
+        name: String,
+        record_type: String,
+        value: String,
+        ttl: Option<u32>,
+    },
+    Certificate {
+        subject: String,
+        issuer: Option<String>,
+        not_before: Option<String>,
+        not_after: Option<String>,
+        serial: Option<String>,
+    },
+    Url {
+        url: String,
+    },
+    WhoisField {
+        key: String,
+        value: String,
+    },
+    Organization {
+        name: String,
+    },
+    Asn {
+        number: u32,
+        name: Option<String>,
+    },
+    OperatingSystem {
+        ip: IpAddr,
+        name: String,
+    },
+    VirtualHost {
+        ip: IpAddr,
+        hostname: String,
+    },
+    /// A profile page on a social network found for the target.
+    SocialProfile {
+        network: String,
+        name: Option<String>,
+        handle: Option<String>,
+        url: String,
+    },
+    /// A keyserver user ID carrying an address at the target domain.
+    PgpKey {
+        fingerprint: String,
+        user_id: String,
+        email: String,
+    },
+    /// Outcome of an AXFR attempt against one address of an authoritative name server.
+    ZoneTransfer {
+        nameserver: String,
+        ip: IpAddr,
+        allowed: bool,
+    },
+    /// A known vulnerability (CVE) of a host.
+    Vulnerability {
+        ip: IpAddr,
+        id: String,
+    },
+    /// Number of search results sharing one value of a facet (e.g. Shodan's `org` or `port`).
+    Facet {
+        facet: String,
+        value: String,
+        count: u64,
+    },
+    /// A hostname a source has tied to an IP address (e.g. Shodan's `hostnames` for a host).
+    Resolution {
+        hostname: String,
+        ip: IpAddr,
+    },
+}
+
+impl Finding {
+    pub fn hostname(name: impl Into<String>) -> Self {
+        Finding::Hostname { name: name.into() }
+    }
+
+    pub fn email(address: impl Into<String>) -> Self {
+        Finding::Email {
+            address: address.into(),
+        }
+    }
+
+    pub fn url(url: impl Into<String>) -> Self {
+        Finding::Url { url: url.into() }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Finding::Hostname { name } => write!(f, "Hostname: {}", name),
+            Finding::IpAddress { address } => write!(f, "IP: {}", address),
+            Finding::Email { address } => write!(f, "Email: {}", address),
+            Finding::OpenPort {
+                ip,
+                port,
+                transport,
+                service,
+            } => {
+                write!(f, "Open Port: {}:{}", ip, port)?;
+                if let Some(transport) = transport {
+                    write!(f, "/{}", transport)?;
+                }
+                if let Some(service) = service {
+                    write!(f, " ({})", service)?;
+                }
+                Ok(())
+            }
+            Finding::DnsRecord {
+                name,
+                record_type,
+                value,
+                ttl,
+            } => {
+                write!(f, "{} Record: {} -> {}", record_type, name, value)?;
+                if let Some(ttl) = ttl {
+                    write!(f, " (TTL {})", ttl)?;
+                }
+                Ok(())
+            }
+            Finding::Certificate {
+                subject,
+                issuer,
+                not_after,
+                ..
+            } => {
+                write!(f, "Certificate: {}", subject)?;
+                if let Some(issuer) = issuer {
+                    write!(f, " issued by {}", issuer)?;
+                }
+                if let Some(not_after) = not_after {
+                    write!(f, " valid until {}", not_after)?;
+                }
+                Ok(())
+            }
+            Finding::Url { url } => write!(f, "URL: {}", url),
+            Finding::WhoisField { key, value } => write!(f, "{}: {}", key, value),
+            Finding::Organization { name } => write!(f, "Organization: {}", name),
+            Finding::Asn { number, name } => match name {
+                Some(name) => write!(f, "ASN: AS{} ({})", number, name),
+                None => write!(f, "ASN: AS{}", number),
+            },
+            Finding::OperatingSystem { ip, name } => {
+                write!(f, "Operating System: {} ({})", name, ip)
+            }
+            Finding::VirtualHost { ip, hostname } => {
+                write!(f, "Virtual Host: {} on {}", hostname, ip)
+            }
+            Finding::SocialProfile {
+                network,
+                name,
+                handle,
+                url,
+            } => {
+                write!(f, "{} Profile: ", network)?;
+                if let Some(name) = name {
+                    write!(f, "{} ", name)?;
+                }
+                if let Some(handle) = handle {
+                    write!(f, "({}) ", handle)?;
+                }
+                write!(f, "{}", url)
+            }
+            Finding::PgpKey {
+                fingerprint,
+                user_id,
+                ..
+            } => write!(f, "PGP Key: {} {}", fingerprint, user_id),
+            Finding::ZoneTransfer {
+                nameserver,
+                ip,
+                allowed,
+            } => write!(
+                f,
+                "Zone Transfer: {} ({}) {}",
+                nameserver,
+                ip,
+                if *allowed { "ALLOWED" } else { "refused" }
+            ),
+            Finding::Vulnerability { ip, id } => write!(f, "Vulnerability: {} ({})", id, ip),
+            Finding::Facet {
+                facet,
+                value,
+                count,
+            } => {
+                write!(f, "Facet: {} {} ({})", facet, value, count)
+            }
+            Finding::Resolution { hostname, ip } => write!(f, "Hostname: {} ({})", hostname, ip),
+        }
+    }
+}
+
+/// What a source knows about one address, stored in a result's metadata under
+/// `hosts`, keyed by IP, and rendered by the reporters as a host report.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HostDetails {
+    pub hostnames: Vec<String>,
+    pub org: Option<String>,
+    pub os: Option<String>,
+    pub ports: Vec<u16>,
+    /// CVE identifiers associated with the host.
+    pub vulns: Vec<String>,
+    pub services: Vec<ServiceBanner>,
+}
+
+/// One service banner of a host.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServiceBanner {
+    pub port: u16,
+    pub transport: Option<String>,
+    pub product: Option<String>,
+    pub version: Option<String>,
+    pub cpe: Vec<String>,
+    pub http_title: Option<String>,
+    pub tls_subject: Option<String>,
+}
@@ -3,7 +3,12 @@ use colored::*;
 use std::error::Error;
 use std::time::Instant;
 
+mod correlation;
+mod findings;
+mod http;
 mod modules;
+mod report;
+mod resolver;
 mod utils;
 mod config;
 
@@ -14,18 +19,82 @@ struct Args {
     #[arg(short, long)]
     domain: Option<String>,
 
-    /// DNS server to use
-    #[arg(short, long)]
-    server: Option<String>,
+    /// DNS servers to use, comma separated (ip, ip:port, [ipv6]:port or hostname)
+    #[arg(short, long, value_delimiter = ',')]
+    server: Vec<String>,
+
+    /// Protocol used to query the --server name servers
+    #[arg(long, value_enum, default_value_t = config::DnsProtocol::Udp)]
+    dns_protocol: config::DnsProtocol,
+
+    /// TLS name of the --server name servers (required for tls and https)
+    #[arg(long)]
+    dns_tls_name: Option<String>,
+
+    /// DNS query timeout in seconds
+    #[arg(long, default_value_t = 5)]
+    dns_timeout: u64,
 
-    /// Use a proxy server
+    /// Number of attempts for each DNS query
+    #[arg(long, default_value_t = 2)]
+    dns_retries: usize,
+
+    /// Use a proxy server for all HTTP requests (http://, https://, socks5:// or socks5h://,
+    /// optionally with user:pass@)
     #[arg(short, long)]
     proxy: Option<String>,
 
+    /// IP addresses or CIDR blocks for the revdns module, comma separated
+    /// (defaults to the domain's A/AAAA records)
+    #[arg(long, value_delimiter = ',')]
+    range: Vec<String>,
+
+    /// Subdomain wordlist for the brute module, one label per line
+    #[arg(long)]
+    wordlist: Option<String>,
+
+    /// Maximum brute force queries per second (0 for no limit)
+    #[arg(long, default_value_t = 50)]
+    rate: u32,
+
+    /// Brute force below every subdomain found by the brute module
+    #[arg(long)]
+    recursive: bool,
+
+    /// HKP keyservers for the pgp module, comma separated
+    /// (defaults to keyserver.ubuntu.com and pgp.mit.edu)
+    #[arg(long, value_delimiter = ',')]
+    keyserver: Vec<String>,
+
+    /// File of user agents to rotate between requests, one per line
+    /// (defaults to common-ua.txt when present)
+    #[arg(long)]
+    user_agents: Option<String>,
+
+    /// Minimum delay in seconds between result pages of scraping modules
+    #[arg(long, default_value_t = 2)]
+    delay_min: u64,
+
+    /// Maximum delay in seconds between result pages of scraping modules
+    #[arg(long, default_value_t = 5)]
+    delay_max: u64,
+
+    /// HTTP request timeout in seconds
+    #[arg(long, default_value_t = 10)]
+    timeout: u64,
+
+    /// Accept invalid TLS certificates
+    #[arg(long)]
+    insecure: bool,
+
     /// Limit the number of search engine results
     #[arg(short, long, default_value_t = 100)]
     limit: u32,
 
+    /// Maximum number of HTTP requests and DNS queries in flight at the same time
+    #[arg(long, default_value_t = 5)]
+    concurrency: usize,
+
     /// Information gathering mode
     #[arg(short, long)]
     info: Option<String>,
@@ -34,12 +103,16 @@ struct Args {
     #[arg(short, long)]
     output: Option<String>,
 
+    /// Report formats to write with --output (txt,md,xml,html,json)
+    #[arg(short, long, value_delimiter = ',', default_value = "json")]
+    format: Vec<String>,
+
     /// Shodan API key
-    #[arg(short, long)]
+    #[arg(short = 'k', long)]
     shodan_key: Option<String>,
 
     /// Spyse API key
-    #[arg(short, long)]
+    #[arg(short = 'e', long)]
     spyse_key: Option<String>,
 
     /// Censys API ID
@@ -50,13 +123,57 @@ struct Args {
     #[arg(long)]
     censys_api_secret: Option<String>,
 
+    /// Censys labels the hosts must carry, comma separated
+    #[arg(long, value_delimiter = ',')]
+    tags: Vec<String>,
+
+    /// Censys filter on the autonomous system number
+    #[arg(long)]
+    asn: Option<u32>,
+
+    /// Censys filter on the TLS certificate subject organization
+    #[arg(long)]
+    cert_org: Option<String>,
+
+    /// Censys filter on the TLS certificate issuer organization
+    #[arg(long)]
+    cert_issuer: Option<String>,
+
+    /// Censys filter on the TLS certificate subject common name
+    #[arg(long)]
+    cert_host: Option<String>,
+
+    /// Censys filter on the two-letter country code
+    #[arg(long)]
+    country: Option<String>,
+
+    /// Censys filter on the HTTP Server header
+    #[arg(long)]
+    http_server: Option<String>,
+
+    /// Censys filter on the HTML title
+    #[arg(long)]
+    html_title: Option<String>,
+
+    /// Censys filter on the HTML body
+    #[arg(long)]
+    html_body: Option<String>,
+
+    /// VirusTotal API key
+    #[arg(long)]
+    virustotal_key: Option<String>,
+
     /// Debug mode
-    #[arg(short, long)]
+    #[arg(short = 'D', long)]
     debug: bool,
 
     /// Verbose output
     #[arg(short, long)]
     verbose: bool,
+
+    /// List the available modules and exit
+    #[arg(long)]
+    list_modules: bool,
 }
 
 #[tokio::main]
@@ -70,17 +187,43 @@ async fn main() -> Result<(), Box<dyn Error>> {
     // Print banner
     print_banner();
 
+    // Listing modules needs neither the configuration nor its network clients
+    if args.list_modules {
+        print_module_list(&modules::Services::unconfigured());
+        return Ok(());
+    }
+
     // Initialize configuration
     let config = config::Config::new(&args)?;
+    let services = modules::Services::from_config(&config)?;
+
+    // Resolve the report writers before spending time on the scan
+    let mut reporters = Vec::new();
+    for format in &args.format {
+        match report::get_reporter_by_name(format.trim()) {
+            Some(reporter) => reporters.push(reporter),
+            None => {
+                return Err(format!(
+                    "Unknown report format: {} (available: {})",
+                    format,
+                    report::get_available_formats().join(", ")
+                )
+                .into())
+            }
+        }
+    }
 
     // Run the selected modules
-    if let Some(domain) = args.domain {
+    if let Some(domain) = &args.domain {
         let start_time = Instant::now();
-        let results = run_modules(&domain, &config, &args).await?;
+        let results = run_modules(domain, &config, &services, &args).await?;
         let duration = start_time.elapsed();
 
+        // Merge the findings of all modules
+        let inventory = correlation::correlate(domain, &results);
+
         // Print results
-        print_results(&results, &args, duration)?;
+        print_results(&results, &inventory, &reporters, &args, duration)?;
     }
 
     Ok(())
@@ -105,6 +248,7 @@ GasMasK is an open source tool licensed under GPLv3.
 async fn run_modules(
     domain: &str,
     config: &config::Config,
+    services: &modules::Services,
     args: &Args,
 ) -> Result<Vec<modules::ModuleResult>, Box<dyn Error>> {
     let mut results = Vec::new();
@@ -116,23 +260,79 @@ async fn run_modules(
         vec!["basic"]
     };
 
+    // Resolve every module and check its credentials before starting the scan
+    let mut modules = Vec::new();
     for module_name in modules_to_run {
-        if let Some(module) = modules::get_module_by_name(module_name) {
-            println!("Running {} module...", module.name().green());
-            match module.run(domain, config).await {
-                Ok(result) => results.push(result),
-                Err(e) => eprintln!("Error running {} module: {}", module.name().red(), e),
+        if let Some(module) = modules::get_module_by_name(module_name, services) {
+            let missing = module.missing_credentials(config);
+            if missing.is_empty() {
+                modules.push(module);
+            } else {
+                for credential in missing {
+                    eprintln!(
+                        "Skipping {} module: {} not provided (use {} or api_keys.txt)",
+                        module.name().red(),
+                        credential,
+                        credential.flag()
+                    );
+                }
             }
         } else {
-            eprintln!("Unknown module: {}", module_name.red());
+            eprintln!(
+                "Unknown module: {} (available: {})",
+                module_name.red(),
+                modules::get_available_modules().join(", ")
+            );
+        }
+    }
+
+    for module in &modules {
+        println!("Running {} module...", module.name().green());
+    }
+    let outcomes =
+        modules::run_concurrently(modules.iter().map(|module| module.as_ref()).collect(), domain, config)
+            .await;
+
+    for (module, outcome) in modules.iter().zip(outcomes) {
+        match outcome {
+            Ok(result) => results.push(result),
+            Err(e) => eprintln!("Error running {} module: {}", module.name().red(), e),
         }
     }
 
     Ok(results)
 }
 
+fn print_module_list(services: &modules::Services) {
+    println!(
+        "{:<12} {:<17} {:<9} {:<40} Description",
+        "Module", "Category", "Footprint", "Credentials"
+    );
+    println!("{}", "-".repeat(120));
+
+    for module in modules::get_all_modules(services) {
+        let credentials = module
+            .credentials()
+            .iter()
+            .map(|credential| credential.to_string())
+            .collect::<Vec<String>>()
+            .join(", ");
+
+        println!(
+            "{:<12} {:<17} {:<9} {:<40} {}",
+            module.name(),
+            module.category().to_string(),
+            module.footprint().to_string(),
+            if credentials.is_empty() { "-".to_string() } else { credentials },
+            module.description()
+        );
+    }
+}
+
 fn print_results(
     results: &[modules::ModuleResult],
+    inventory: &correlation::Inventory,
+    reporters: &[Box<dyn report::Reporter>],
     args: &Args,
     duration: std::time::Duration,
 ) -> Result<(), Box<dyn Error>> {
@@ -143,9 +343,18 @@ fn print_results(
         println!("\nSource: {}", result.source.bold());
         println!("{}", "-".repeat(80));
 
-        // Print data
-        for item in &result.data {
-            println!("{}", item);
+        // Print findings
+        for finding in report::listed(result) {
+            println!("{}", finding);
+        }
+
+        // Print facet counts as a summary table
+        let facets = report::facets(result);
+        if !facets.is_empty() {
+            println!("\n{:<10} {:<40} Count", "Facet", "Value");
+            for (facet, value, count) in facets {
+                println!("{:<10} {:<40} {}", facet, value, count);
+            }
         }
 
         // Print metadata if verbose
@@ -157,15 +366,42 @@ fn print_results(
         }
     }
 
+    for (source, hosts) in report::host_reports(results) {
+        let mut host_report = String::new();
+        report::write_host_report(&mut host_report, &hosts)?;
+        println!("\n{}", format!("{} Report", source).bold());
+        println!("{}", "=".repeat(80));
+        print!("{}", host_report);
+    }
+
+    print_final_report(inventory)?;
+
     println!("\nScan completed in {}", utils::format_duration(duration).green());
 
     // Save results if output path is specified
     if let Some(output) = &args.output {
         let output_path = utils::sanitize_filename(output);
-        let json = serde_json::to_string_pretty(&results)?;
-        utils::save_results_to_file(&format!("{}.json", output_path), &json, "json")?;
-        println!("Results saved to {}.json", output_path);
+        let report = report::Report {
+            domain: &inventory.domain,
+            results,
+            inventory,
+        };
+        for reporter in reporters {
+            let extension = reporter.extension();
+            let content = reporter.render(&report)?;
+            utils::save_results_to_file(&format!("{}.{}", output_path, extension), &content, extension)?;
+            println!("Results saved to {}.{}", output_path, extension);
+        }
     }
 
     Ok(())
-} 
\ No newline at end of file
+}
+
+fn print_final_report(inventory: &correlation::Inventory) -> Result<(), Box<dyn Error>> {
+    let mut final_report = String::new();
+    report::write_final_report(&mut final_report, inventory)?;
+    println!("\n{}", "Final Report".bold());
+    println!("{}", "=".repeat(80));
+    print!("{}", final_report);
+    Ok(())
+}
@@ -0,0 +1,251 @@
+use crate::config::Config;
+use crate::findings::Finding;
+use crate::modules::{Category, Footprint, Module, ModuleResult};
+use crate::utils;
+use async_trait::async_trait;
+use futures::future::{join_all, BoxFuture};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, Semaphore};
+use tokio::time::{interval, Interval, MissedTickBehavior};
+use trust_dns_resolver::TokioAsyncResolver;
+
+/// Random labels resolved under each zone to detect wildcard records.
+const WILDCARD_PROBES: usize = 3;
+
+/// How many levels below the target domain `--recursive` descends.
+const MAX_RECURSION_DEPTH: usize = 3;
+
+/// A candidate hostname and the (address, TTL) pairs it resolved to.
+type Answer = (String, Vec<(IpAddr, u32)>);
+
+pub struct BruteForceModule {
+    resolver: TokioAsyncResolver,
+    permits: Arc<Semaphore>,
+}
+
+impl BruteForceModule {
+    pub fn new(resolver: TokioAsyncResolver, permits: Arc<Semaphore>) -> Self {
+        Self { resolver, permits }
+    }
+
+    /// Returns the A/AAAA records of `hostname` as (address, TTL) pairs.
+    async fn resolve(&self, hostname: &str) -> Result<Vec<(IpAddr, u32)>, Box<dyn Error>> {
+        let _permit = self.permits.acquire().await?;
+        let response = self.resolver.lookup_ip(hostname).await?;
+        Ok(response
+            .as_lookup()
+            .record_iter()
+            .filter_map(|record| {
+                let ip = record.data()?.ip_addr()?;
+                Some((ip, record.ttl()))
+            })
+            .collect())
+    }
+
+    /// Addresses returned for random labels under `zone`, i.e. its wildcard answers.
+    async fn wildcard_ips(&self, zone: &str) -> BTreeSet<IpAddr> {
+        let mut ips = BTreeSet::new();
+        for _ in 0..WILDCARD_PROBES {
+            let label: String = rand::thread_rng()
+                .sample_iter(&Alphanumeric)
+                .take(16)
+                .map(|c| char::from(c).to_ascii_lowercase())
+                .collect();
+            if let Ok(answers) = self.resolve(&format!("{}.{}", label, zone)).await {
+                ips.extend(answers.into_iter().map(|(ip, _)| ip));
+            }
+        }
+        ips
+    }
+
+    /// Resolves `word.zone` for every word, within the shared concurrency permits
+    /// and no more than `config.brute_rate` queries per second.
+    async fn brute_zone(
+        &self,
+        zone: &str,
+        words: &[String],
+        config: &Config,
+    ) -> Vec<Answer> {
+        let limiter: Option<Mutex<Interval>> = (config.brute_rate > 0).then(|| {
+            let mut ticker = interval(Duration::from_secs(1) / config.brute_rate);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            Mutex::new(ticker)
+        });
+        let limiter = &limiter;
+
+        let lookups: Vec<BoxFuture<'_, Answer>> = words
+            .iter()
+            .map(|word| -> BoxFuture<'_, Answer> {
+                let hostname = format!("{}.{}", word, zone);
+                Box::pin(async move {
+                    if let Some(limiter) = limiter {
+                        limiter.lock().await.tick().await;
+                    }
+                    let answers = self.resolve(&hostname).await.unwrap_or_default();
+                    (hostname, answers)
+                })
+            })
+            .collect();
+
+        join_all(lookups)
+            .await
+            .into_iter()
+            .filter(|(_, answers)| !answers.is_empty())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Module for BruteForceModule {
+    fn name(&self) -> &'static str {
+        "brute"
+    }
+
+    fn description(&self) -> &'static str {
+        "DNS subdomain brute force from a --wordlist"
+    }
+
+    fn category(&self) -> Category {
+        Category::Dns
+    }
+
+    fn footprint(&self) -> Footprint {
+        Footprint::Active
+    }
+
+    async fn run(&self, domain: &str, config: &Config) -> Result<ModuleResult, Box<dyn Error>> {
+        let words = load_words(config.wordlist.as_deref())?;
+
+        let mut findings = Vec::new();
+        let mut found = Vec::new();
+        let mut wildcards = serde_json::Map::new();
+        let mut zones = vec![domain.to_lowercase()];
+
+        for depth in 0..MAX_RECURSION_DEPTH {
+            let mut next_zones = Vec::new();
+            for zone in &zones {
+                let wildcard = self.wildcard_ips(zone).await;
+                if !wildcard.is_empty() {
+                    wildcards.insert(zone.clone(), json!(wildcard));
+                }
+
+                let answers = self.brute_zone(zone, &words, config).await;
+                for (hostname, answers) in without_wildcards(answers, &wildcard) {
+                    for (ip, ttl) in &answers {
+                        findings.push(Finding::DnsRecord {
+                            name: hostname.clone(),
+                            record_type: if ip.is_ipv4() { "A" } else { "AAAA" }.to_string(),
+                            value: ip.to_string(),
+                            ttl: Some(*ttl),
+                        });
+                    }
+                    found.push(hostname.clone());
+                    next_zones.push(hostname);
+                }
+            }
+
+            if !descend(config.recursive, depth, &next_zones) {
+                break;
+            }
+            zones = next_zones;
+        }
+
+        Ok(ModuleResult {
+            source: "DNS Brute Force".to_string(),
+            findings,
+            metadata: Some(json!({
+                "wordlist_size": words.len(),
+                "subdomains": found,
+                "wildcards": wildcards,
+            })),
+        })
+    }
+}
+
+/// Words of the `--wordlist` at `path`, or the built-in common subdomains without one.
+fn load_words(path: Option<&str>) -> Result<Vec<String>, Box<dyn Error>> {
+    match path {
+        Some(path) => read_wordlist(path),
+        None => Ok(utils::COMMON_SUBDOMAINS.iter().map(|word| word.to_string()).collect()),
+    }
+}
+
+/// Drops answers made up entirely of the zone's `wildcard` addresses, which are
+/// not real subdomains.
+fn without_wildcards(answers: Vec<Answer>, wildcard: &BTreeSet<IpAddr>) -> Vec<Answer> {
+    answers
+        .into_iter()
+        .filter(|(_, answers)| !answers.iter().all(|(ip, _)| wildcard.contains(ip)))
+        .collect()
+}
+
+/// Whether `--recursive` brute forcing goes on below the `zones` found at `depth`.
+fn descend(recursive: bool, depth: usize, zones: &[String]) -> bool {
+    recursive && depth + 1 < MAX_RECURSION_DEPTH && !zones.is_empty()
+}
+
+/// One label per line; blank lines and `#` comments are skipped.
+fn read_wordlist(path: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let contents = fs::read_to_string(path).map_err(|e| format!("Could not read wordlist {}: {}", path, e))?;
+    let words: BTreeSet<String> = contents
+        .lines()
+        .map(|line| line.trim().trim_matches('.').to_lowercase())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+    Ok(words.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_wordlists_and_falls_back_to_common_subdomains() {
+        let path = std::env::temp_dir().join(format!("gasmask-wordlist-{}.txt", std::process::id()));
+        fs::write(&path, "# admin panels\nAdmin\n\n  vpn.  \nadmin\n#www\n").unwrap();
+        let words = load_words(path.to_str());
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(words.unwrap(), ["admin", "vpn"]);
+        assert_eq!(load_words(None).unwrap().len(), utils::COMMON_SUBDOMAINS.len());
+        assert!(load_words(Some("/nonexistent/wordlist.txt")).is_err());
+    }
+
+    #[test]
+    fn drops_answers_made_up_of_wildcard_addresses() {
+        let wildcard: BTreeSet<IpAddr> = ["192.0.2.1".parse().unwrap()].into();
+        let answers = vec![
+            ("www.example.com".to_string(), vec![("192.0.2.1".parse().unwrap(), 300)]),
+            (
+                "mail.example.com".to_string(),
+                vec![("192.0.2.1".parse().unwrap(), 300), ("192.0.2.25".parse().unwrap(), 300)],
+            ),
+            ("vpn.example.com".to_string(), vec![("198.51.100.7".parse().unwrap(), 60)]),
+        ];
+
+        let hostnames: Vec<String> = without_wildcards(answers, &wildcard)
+            .into_iter()
+            .map(|(hostname, _)| hostname)
+            .collect();
+        assert_eq!(hostnames, ["mail.example.com", "vpn.example.com"]);
+    }
+
+    #[test]
+    fn descends_only_when_recursive_and_within_the_depth_limit() {
+        let zones = vec!["dev.example.com".to_string()];
+
+        assert!(!descend(false, 0, &zones));
+        assert!(descend(true, 0, &zones));
+        assert!(descend(true, MAX_RECURSION_DEPTH - 2, &zones));
+        assert!(!descend(true, MAX_RECURSION_DEPTH - 1, &zones));
+        assert!(!descend(true, 0, &[]));
+    }
+}
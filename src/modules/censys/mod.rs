@@ -1,46 +1,112 @@
 use crate::config::Config;
-use crate::modules::{Module, ModuleResult};
+use crate::findings::Finding;
+use crate::http::{self, HttpClient, RateLimiter};
+use crate::modules::{Category, Credential, Footprint, Module, ModuleResult};
 use async_trait::async_trait;
+use futures::future::join_all;
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
-use reqwest::Client;
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 use std::error::Error;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+/// Largest page size the Search v2 hosts endpoint accepts.
+const PAGE_SIZE: u32 = 100;
+
+/// Filters narrowing the Censys host search, as gasmask.py's `build_query_string`
+/// supported them. Each one is ANDed to the `names` query of the target domain.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CensysQuery {
+    pub tags: Vec<String>,
+    pub asn: Option<u32>,
+    pub cert_org: Option<String>,
+    pub cert_issuer: Option<String>,
+    pub cert_host: Option<String>,
+    pub country: Option<String>,
+    pub http_server: Option<String>,
+    pub html_title: Option<String>,
+    pub html_body: Option<String>,
+}
+
+impl CensysQuery {
+    /// Search v2 query for `domain` with every filter that is set.
+    pub fn build(&self, domain: &str) -> String {
+        let mut terms = vec![format!("names: {}", quote(domain))];
+        terms.extend(self.tags.iter().map(|tag| format!("labels: {}", quote(tag))));
+        if let Some(asn) = self.asn {
+            terms.push(format!("autonomous_system.asn: {}", asn));
+        }
+
+        let fields = [
+            ("services.tls.certificates.leaf_data.subject.organization", &self.cert_org),
+            ("services.tls.certificates.leaf_data.issuer.organization", &self.cert_issuer),
+            ("services.tls.certificates.leaf_data.subject.common_name", &self.cert_host),
+            ("location.country_code", &self.country),
+            ("services.http.response.headers.server", &self.http_server),
+            ("services.http.response.html_title", &self.html_title),
+            ("services.http.response.body", &self.html_body),
+        ];
+        for (field, value) in fields {
+            if let Some(value) = value {
+                terms.push(format!("{}: {}", field, quote(value)));
+            }
+        }
+
+        terms.join(" and ")
+    }
+}
+
+/// Quotes `value` unless it is a plain word, escaping quotes and backslashes.
+fn quote(value: &str) -> String {
+    let plain = value.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'));
+    if plain {
+        value.to_string()
+    } else {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
 
 pub struct CensysModule {
-    client: Client,
+    client: HttpClient,
+    limiter: Arc<RateLimiter>,
 }
 
 impl CensysModule {
-    pub fn new() -> Self {
-        Self {
-            client: Client::new(),
-        }
+    pub fn new(client: HttpClient, limiter: Arc<RateLimiter>) -> Self {
+        Self { client, limiter }
     }
 
+    /// One page of `query` results, continuing from `cursor` if given.
     async fn search_hosts(
         &self,
         api_id: &str,
         api_secret: &str,
         query: &str,
-        limit: u32,
+        per_page: u32,
+        cursor: Option<&str>,
     ) -> Result<serde_json::Value, Box<dyn Error>> {
         let auth = format!("{}:{}", api_id, api_secret);
         let auth_b64 = BASE64.encode(auth.as_bytes());
 
-        let url = format!(
+        let mut url = format!(
             "https://search.censys.io/api/v2/hosts/search?q={}&per_page={}",
             urlencoding::encode(query),
-            limit
+            per_page.min(PAGE_SIZE)
         );
+        if let Some(cursor) = cursor {
+            url.push_str(&format!("&cursor={}", urlencoding::encode(cursor)));
+        }
 
-        let response = self.client
+        let request = self.client
             .get(&url)
-            .header("Authorization", format!("Basic {}", auth_b64))
-            .send()
-            .await?;
+            .header("Authorization", format!("Basic {}", auth_b64));
+        let response = self.limiter.send(&self.client, request).await?;
 
-        let data = response.json::<serde_json::Value>().await?;
-        Ok(data)
+        http::json(response).await
     }
 
     async fn get_host_details(
@@ -54,14 +120,12 @@ impl CensysModule {
 
         let url = format!("https://search.censys.io/api/v2/hosts/{}", ip);
 
-        let response = self.client
+        let request = self.client
             .get(&url)
-            .header("Authorization", format!("Basic {}", auth_b64))
-            .send()
-            .await?;
+            .header("Authorization", format!("Basic {}", auth_b64));
+        let response = self.limiter.send(&self.client, request).await?;
 
-        let data = response.json::<serde_json::Value>().await?;
-        Ok(data)
+        http::json(response).await
     }
 }
 
@@ -75,58 +139,151 @@ impl Module for CensysModule {
         "Censys information gathering module"
     }
 
+    fn category(&self) -> Category {
+        Category::HostSearch
+    }
+
+    fn footprint(&self) -> Footprint {
+        Footprint::Passive
+    }
+
+    fn credentials(&self) -> &'static [Credential] {
+        &[Credential::CensysApiId, Credential::CensysApiSecret]
+    }
+
     async fn run(&self, domain: &str, config: &Config) -> Result<ModuleResult, Box<dyn Error>> {
         let api_id = config.censys_api_id.as_ref().ok_or("Censys API ID not provided")?;
         let api_secret = config.censys_api_secret.as_ref().ok_or("Censys API Secret not provided")?;
         
-        let mut data = Vec::new();
+        let mut findings = Vec::new();
         let mut metadata = json!({});
 
         // Search for hosts
-        let search_results = self.search_hosts(api_id, api_secret, &format!("names:{}", domain), config.limit).await?;
-        
-        if let Some(results) = search_results.get("result").and_then(|v| v.as_array()) {
-            for result in results {
-                if let Some(ip) = result.get("ip").and_then(|v| v.as_str()) {
-                    data.push(format!("IP: {}", ip));
-
-                    // Get detailed host information
-                    if let Ok(host_info) = self.get_host_details(api_id, api_secret, ip).await {
-                        if let Some(names) = host_info.get("names").and_then(|v| v.as_array()) {
-                            for name in names {
-                                if let Some(hostname) = name.as_str() {
-                                    data.push(format!("Hostname: {}", hostname));
-                                }
-                            }
-                        }
+        let query = config.censys_query.build(domain);
+        metadata["query"] = json!(query);
+        let mut addresses: Vec<IpAddr> = Vec::new();
+        let mut cursor: Option<String> = None;
 
-                        if let Some(ports) = host_info.get("ports").and_then(|v| v.as_array()) {
-                            for port in ports {
-                                if let Some(port_num) = port.as_u64() {
-                                    data.push(format!("Open Port: {}", port_num));
-                                }
-                            }
+        // Follow the `links.next` cursor until the hits run out or reach the limit
+        while addresses.len() < config.limit as usize {
+            let page = self
+                .search_hosts(api_id, api_secret, &query, config.limit, cursor.as_deref())
+                .await?;
+            let hits = page
+                .pointer("/result/hits")
+                .and_then(|v| v.as_array())
+                .cloned()
+                .unwrap_or_default();
+            if hits.is_empty() {
+                break;
+            }
+            for hit in &hits {
+                let address = hit
+                    .get("ip")
+                    .and_then(|v| v.as_str())
+                    .and_then(|ip| ip.parse().ok());
+                if let Some(address) = address {
+                    if !addresses.contains(&address) {
+                        addresses.push(address);
+                    }
+                }
+            }
+
+            cursor = page
+                .pointer("/result/links/next")
+                .and_then(|v| v.as_str())
+                .filter(|next| !next.is_empty())
+                .map(String::from);
+            if cursor.is_none() {
+                break;
+            }
+        }
+        addresses.truncate(config.limit as usize);
+
+        // Get detailed host information, as many at a time as the client permits
+        let host_infos = join_all(addresses.into_iter().map(|address| async move {
+            let host_info = self
+                .get_host_details(api_id, api_secret, &address.to_string())
+                .await
+                .ok();
+            (address, host_info)
+        }))
+        .await;
+
+        let mut hosts = serde_json::Map::new();
+        for (address, host_info) in host_infos {
+            findings.push(Finding::IpAddress { address });
+
+            if let Some(host_info) = host_info {
+                if let Some(names) = host_info.get("names").and_then(|v| v.as_array()) {
+                    for name in names {
+                        if let Some(hostname) = name.as_str() {
+                            findings.push(Finding::Resolution {
+                                hostname: hostname.to_string(),
+                                ip: address,
+                            });
                         }
+                    }
+                }
 
-                        if let Some(autonomous_system) = host_info.get("autonomous_system") {
-                            if let Some(asn) = autonomous_system.get("asn").and_then(|v| v.as_u64()) {
-                                data.push(format!("ASN: {}", asn));
-                            }
-                            if let Some(org) = autonomous_system.get("name").and_then(|v| v.as_str()) {
-                                data.push(format!("Organization: {}", org));
-                            }
+                if let Some(ports) = host_info.get("ports").and_then(|v| v.as_array()) {
+                    for port in ports {
+                        if let Some(port_num) = port.as_u64() {
+                            findings.push(Finding::OpenPort {
+                                ip: address,
+                                port: port_num as u16,
+                                transport: None,
+                                service: None,
+                            });
                         }
+                    }
+                }
 
-                        metadata["host_info"] = host_info;
+                if let Some(autonomous_system) = host_info.get("autonomous_system") {
+                    let org = autonomous_system.get("name").and_then(|v| v.as_str());
+                    if let Some(asn) = autonomous_system.get("asn").and_then(|v| v.as_u64()) {
+                        findings.push(Finding::Asn {
+                            number: asn as u32,
+                            name: org.map(String::from),
+                        });
+                    }
+                    if let Some(org) = org {
+                        findings.push(Finding::Organization { name: org.to_string() });
                     }
                 }
+
+                hosts.insert(address.to_string(), host_info);
             }
         }
+        metadata["host_info"] = hosts.into();
 
         Ok(ModuleResult {
             source: "Censys".to_string(),
-            data,
+            findings,
             metadata: Some(metadata),
         })
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_search_v2_queries() {
+        assert_eq!(CensysQuery::default().build("example.com"), "names: example.com");
+
+        let query = CensysQuery {
+            tags: vec!["login-page".to_string(), "remote access".to_string()],
+            asn: Some(64496),
+            cert_issuer: Some("Let's Encrypt".to_string()),
+            country: Some("DE".to_string()),
+            html_title: Some(r#"Welcome to "nginx""#.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            query.build("example.com"),
+            r#"names: example.com and labels: login-page and labels: "remote access" and autonomous_system.asn: 64496 and services.tls.certificates.leaf_data.issuer.organization: "Let's Encrypt" and location.country_code: DE and services.http.response.html_title: "Welcome to \"nginx\"""#
+        );
+    }
+}
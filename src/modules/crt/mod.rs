@@ -0,0 +1,207 @@
+use crate::config::Config;
+use crate::findings::Finding;
+use crate::http::HttpClient;
+use crate::modules::{Category, Footprint, Module, ModuleResult};
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+
+const CRT_URL: &str = "https://crt.sh/";
+
+/// One certificate of crt.sh's `output=json` listing.
+#[derive(Debug, Deserialize)]
+struct CrtEntry {
+    issuer_name: Option<String>,
+    common_name: Option<String>,
+    /// SANs of the certificate, one per line
+    name_value: String,
+    not_before: Option<String>,
+    not_after: Option<String>,
+    serial_number: Option<String>,
+}
+
+pub struct CrtModule {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl CrtModule {
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_url(client, CRT_URL)
+    }
+
+    /// Queries a crt.sh-compatible endpoint other than crt.sh itself.
+    pub fn with_base_url(client: HttpClient, base_url: &str) -> Self {
+        Self {
+            client,
+            base_url: base_url.to_string(),
+        }
+    }
+
+    async fn search(&self, domain: &str) -> Result<Vec<CrtEntry>, Box<dyn Error>> {
+        let url = format!(
+            "{}?q={}&output=json",
+            self.base_url,
+            urlencoding::encode(&format!("%.{}", domain))
+        );
+
+        let request = self.client.get(&url);
+        let response = self.client.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(format!("Unexpected status code: {}", response.status()).into());
+        }
+
+        Ok(response.json::<Vec<CrtEntry>>().await?)
+    }
+}
+
+#[async_trait]
+impl Module for CrtModule {
+    fn name(&self) -> &'static str {
+        "crt"
+    }
+
+    fn description(&self) -> &'static str {
+        "Certificate Transparency (crt.sh) subdomain module"
+    }
+
+    fn category(&self) -> Category {
+        Category::Certificates
+    }
+
+    fn footprint(&self) -> Footprint {
+        Footprint::Passive
+    }
+
+    async fn run(&self, domain: &str, _config: &Config) -> Result<ModuleResult, Box<dyn Error>> {
+        let entries = self.search(domain).await?;
+        let domain = domain.to_lowercase();
+        let suffix = format!(".{}", domain);
+
+        let mut subdomains: BTreeMap<String, Vec<serde_json::Value>> = BTreeMap::new();
+        let mut certificates = Vec::new();
+        let mut serials = BTreeSet::new();
+
+        for entry in &entries {
+            let names: BTreeSet<String> = entry
+                .name_value
+                .lines()
+                .map(|name| name.trim().trim_start_matches("*.").to_lowercase())
+                .filter(|name| *name == domain || name.ends_with(&suffix))
+                .collect();
+            if names.is_empty() {
+                continue;
+            }
+
+            let details = json!({
+                "issuer": entry.issuer_name,
+                "not_before": entry.not_before,
+                "not_after": entry.not_after,
+                "serial": entry.serial_number,
+            });
+            for name in &names {
+                subdomains.entry(name.clone()).or_default().push(details.clone());
+            }
+
+            // crt.sh lists a certificate once per log entry, report each serial once
+            let new_serial = match &entry.serial_number {
+                Some(serial) => serials.insert(serial.clone()),
+                None => true,
+            };
+            if new_serial {
+                let subject = entry
+                    .common_name
+                    .clone()
+                    .unwrap_or_else(|| names.iter().next().cloned().unwrap_or_default());
+                certificates.push(Finding::Certificate {
+                    subject,
+                    issuer: entry.issuer_name.clone(),
+                    not_before: entry.not_before.clone(),
+                    not_after: entry.not_after.clone(),
+                    serial: entry.serial_number.clone(),
+                });
+            }
+        }
+
+        // The apex is the target itself, not something crt.sh found
+        let mut findings: Vec<Finding> = subdomains
+            .keys()
+            .filter(|name| **name != domain)
+            .map(Finding::hostname)
+            .collect();
+        findings.extend(certificates);
+
+        Ok(ModuleResult {
+            source: "CRT".to_string(),
+            findings,
+            metadata: Some(json!({
+                "certificates": entries.len(),
+                "subdomains": subdomains,
+            })),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::test_server::{json_response, serve};
+    use clap::Parser;
+
+    #[tokio::test]
+    async fn parses_crt_sh_json() {
+        let body = json_response(
+            r#"[
+                {"issuer_name": "C=US, O=Let's Encrypt, CN=R3", "common_name": "example.com",
+                 "name_value": "example.com\n*.example.com\nwww.example.com",
+                 "not_before": "2024-01-01T00:00:00", "not_after": "2024-04-01T00:00:00",
+                 "serial_number": "04aa"},
+                {"issuer_name": "C=US, O=Let's Encrypt, CN=R3", "common_name": "example.com",
+                 "name_value": "example.com\n*.example.com\nwww.example.com",
+                 "not_before": "2024-01-01T00:00:00", "not_after": "2024-04-01T00:00:00",
+                 "serial_number": "04aa"},
+                {"issuer_name": "C=US, O=DigiCert Inc, CN=DigiCert TLS RSA SHA256 2020 CA1",
+                 "common_name": "mail.example.com", "name_value": "MAIL.example.com\nother.org",
+                 "not_before": "2023-06-01T00:00:00", "not_after": "2024-06-01T00:00:00",
+                 "serial_number": "0b12"}
+            ]"#,
+        );
+        let base_url = serve(&[&body]).await;
+
+        let config = Config::new(&crate::Args::parse_from(["gasmask"])).unwrap();
+        let module = CrtModule::with_base_url(reqwest::Client::new().into(), &base_url);
+        let result = module.run("example.com", &config).await.unwrap();
+
+        let hostnames: Vec<&Finding> = result
+            .findings
+            .iter()
+            .filter(|finding| matches!(finding, Finding::Hostname { .. }))
+            .collect();
+        assert_eq!(
+            hostnames,
+            vec![
+                &Finding::hostname("mail.example.com"),
+                &Finding::hostname("www.example.com"),
+            ]
+        );
+
+        let serials: Vec<Option<&str>> = result
+            .findings
+            .iter()
+            .filter_map(|finding| match finding {
+                Finding::Certificate { serial, .. } => Some(serial.as_deref()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(serials, vec![Some("04aa"), Some("0b12")]);
+
+        let metadata = result.metadata.unwrap();
+        assert_eq!(
+            metadata["subdomains"]["mail.example.com"][0]["not_after"],
+            "2024-06-01T00:00:00"
+        );
+    }
+}
@@ -1,39 +1,165 @@
 use crate::config::Config;
-use crate::modules::{Module, ModuleResult};
+use crate::findings::Finding;
+use crate::modules::{Category, Footprint, Module, ModuleResult};
 use async_trait::async_trait;
+use base64::Engine as _;
+use futures::future::{join_all, BoxFuture};
+use std::collections::HashSet;
 use std::error::Error;
-use trust_dns_resolver::config::*;
-use trust_dns_resolver::Resolver;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::Semaphore;
+use tokio::time::timeout;
+use trust_dns_resolver::proto::op::{Message, Query, ResponseCode};
+use trust_dns_resolver::proto::rr::{Name, RData, Record, RecordType};
+use trust_dns_resolver::TokioAsyncResolver;
+
+/// Record types queried directly on the target domain.
+const DOMAIN_RECORD_TYPES: &[RecordType] = &[
+    RecordType::A,
+    RecordType::AAAA,
+    RecordType::CNAME,
+    RecordType::MX,
+    RecordType::NS,
+    RecordType::SOA,
+    RecordType::TXT,
+    RecordType::CAA,
+    RecordType::HINFO,
+    RecordType::DNSKEY,
+    RecordType::DS,
+];
+
+/// Common service labels looked up as SRV records under the target domain.
+const SRV_LABELS: &[&str] = &[
+    "_ldap._tcp",
+    "_ldap._tcp.dc._msdcs",
+    "_gc._tcp",
+    "_kerberos._tcp",
+    "_kerberos._udp",
+    "_kpasswd._tcp",
+    "_sip._tcp",
+    "_sip._udp",
+    "_sip._tls",
+    "_sipfederationtls._tcp",
+    "_autodiscover._tcp",
+    "_xmpp-client._tcp",
+    "_xmpp-server._tcp",
+    "_caldav._tcp",
+    "_caldavs._tcp",
+    "_carddav._tcp",
+    "_carddavs._tcp",
+    "_imap._tcp",
+    "_imaps._tcp",
+    "_pop3._tcp",
+    "_pop3s._tcp",
+    "_submission._tcp",
+    "_h323cs._tcp",
+];
+
+/// Common DKIM selectors looked up as `<selector>._domainkey.<domain>`.
+const DKIM_SELECTORS: &[&str] = &[
+    "default", "dkim", "google", "k1", "mail", "s1", "s2", "selector1", "selector2",
+];
+
+/// Name server, the address tried and the transferred records or why the transfer failed.
+type Transfer = (String, IpAddr, Result<Vec<Record>, String>);
 
 pub struct DnsModule {
-    resolver: Resolver,
+    resolver: TokioAsyncResolver,
+    permits: Arc<Semaphore>,
+    reverse_lookups: bool,
 }
 
 impl DnsModule {
-    pub fn new() -> Self {
-        let resolver = Resolver::new(ResolverConfig::default(), ResolverOpts::default())
-            .expect("Failed to create DNS resolver");
-        Self { resolver }
+    pub fn new(resolver: TokioAsyncResolver, permits: Arc<Semaphore>) -> Self {
+        Self {
+            resolver,
+            permits,
+            reverse_lookups: true,
+        }
     }
 
-    async fn lookup_a(&self, domain: &str) -> Result<Vec<String>, Box<dyn Error>> {
-        let response = self.resolver.lookup_ip(domain).await?;
-        Ok(response.iter().map(|ip| ip.to_string()).collect())
+    /// A module that leaves PTR lookups of the domain's addresses to `revdns`,
+    /// for composites that run both.
+    pub fn without_reverse_lookups(resolver: TokioAsyncResolver, permits: Arc<Semaphore>) -> Self {
+        Self {
+            reverse_lookups: false,
+            ..Self::new(resolver, permits)
+        }
     }
 
-    async fn lookup_mx(&self, domain: &str) -> Result<Vec<String>, Box<dyn Error>> {
-        let response = self.resolver.mx_lookup(domain).await?;
-        Ok(response.iter().map(|mx| mx.exchange().to_string()).collect())
+    /// Queries `name` for `record_type` and returns every matching answer as a finding.
+    async fn lookup(&self, name: &str, record_type: RecordType) -> Result<Vec<Finding>, Box<dyn Error>> {
+        let _permit = self.permits.acquire().await?;
+        let response = self.resolver.lookup(name, record_type).await?;
+        Ok(answer_findings(response.record_iter(), record_type))
     }
 
-    async fn lookup_ns(&self, domain: &str) -> Result<Vec<String>, Box<dyn Error>> {
-        let response = self.resolver.ns_lookup(domain).await?;
-        Ok(response.iter().map(|ns| ns.to_string()).collect())
+    async fn lookup_ptr(&self, ip: IpAddr) -> Result<Vec<Finding>, Box<dyn Error>> {
+        let _permit = self.permits.acquire().await?;
+        let response = self.resolver.lookup(ip, RecordType::PTR).await?;
+        Ok(response
+            .record_iter()
+            .filter_map(|record| match record.data()? {
+                RData::PTR(ptr) => Some(Finding::DnsRecord {
+                    name: ip.to_string(),
+                    record_type: "PTR".to_string(),
+                    value: ptr.to_string().trim_end_matches('.').to_string(),
+                    ttl: Some(record.ttl()),
+                }),
+                _ => None,
+            })
+            .collect())
     }
 
-    async fn lookup_txt(&self, domain: &str) -> Result<Vec<String>, Box<dyn Error>> {
-        let response = self.resolver.txt_lookup(domain).await?;
-        Ok(response.iter().flat_map(|txt| txt.iter().map(|s| s.to_string())).collect())
+    /// Attempts a zone transfer from every address of every name server in `nameservers`.
+    async fn transfer_zone(
+        &self,
+        domain: &str,
+        nameservers: Vec<String>,
+        config: &Config,
+    ) -> Vec<Transfer> {
+        // A and AAAA separately, so IPv6 addresses are tried even when IPv4 ones exist
+        let mut targets = Vec::new();
+        for nameserver in nameservers {
+            for record_type in [RecordType::A, RecordType::AAAA] {
+                let _permit = self.permits.acquire().await;
+                if let Ok(response) = self.resolver.lookup(nameserver.as_str(), record_type).await {
+                    let ips = response.iter().filter_map(|rdata| rdata.ip_addr());
+                    targets.extend(ips.map(|ip| (nameserver.clone(), ip)));
+                }
+            }
+        }
+
+        let wait = Duration::from_secs(config.dns_timeout);
+        let transfers: Vec<BoxFuture<'_, Transfer>> = targets
+            .into_iter()
+            .map(|(nameserver, ip)| -> BoxFuture<'_, Transfer> {
+                Box::pin(async move {
+                    let _permit = self.permits.acquire().await;
+                    let outcome = axfr(domain, ip, wait).await.map_err(|e| e.to_string());
+                    (nameserver, ip, outcome)
+                })
+            })
+            .collect();
+
+        join_all(transfers).await
+    }
+
+    /// Runs `queries` concurrently, each holding a shared permit; failed lookups
+    /// (including NXDOMAIN and empty answers) contribute no findings.
+    async fn lookup_all(&self, queries: Vec<(String, RecordType)>) -> Vec<Finding> {
+        let lookups: Vec<BoxFuture<'_, Vec<Finding>>> = queries
+            .into_iter()
+            .map(|(name, record_type)| -> BoxFuture<'_, Vec<Finding>> {
+                Box::pin(async move { self.lookup(&name, record_type).await.unwrap_or_default() })
+            })
+            .collect();
+
+        join_all(lookups).await.into_iter().flatten().collect()
     }
 }
 
@@ -47,32 +173,341 @@ impl Module for DnsModule {
         "DNS information gathering module"
     }
 
-    async fn run(&self, domain: &str, _config: &Config) -> Result<ModuleResult, Box<dyn Error>> {
-        let mut data = Vec::new();
-        let mut metadata = serde_json::json!({});
+    fn category(&self) -> Category {
+        Category::Dns
+    }
 
-        // Perform various DNS lookups
-        if let Ok(ips) = self.lookup_a(domain).await {
-            data.extend(ips);
-            metadata["a_records"] = serde_json::json!(ips);
-        }
+    fn footprint(&self) -> Footprint {
+        Footprint::Active
+    }
+
+    async fn run(&self, domain: &str, config: &Config) -> Result<ModuleResult, Box<dyn Error>> {
+        let mut queries: Vec<(String, RecordType)> = DOMAIN_RECORD_TYPES
+            .iter()
+            .map(|record_type| (domain.to_string(), *record_type))
+            .collect();
+        queries.extend(SRV_LABELS.iter().map(|label| (format!("{}.{}", label, domain), RecordType::SRV)));
+        queries.push((format!("_dmarc.{}", domain), RecordType::TXT));
+        queries.extend(
+            DKIM_SELECTORS
+                .iter()
+                .map(|selector| (format!("{}._domainkey.{}", selector, domain), RecordType::TXT)),
+        );
 
-        if let Ok(mx) = self.lookup_mx(domain).await {
-            metadata["mx_records"] = serde_json::json!(mx);
+        let mut findings = self.lookup_all(queries).await;
+
+        // Reverse lookups for the addresses the domain resolves to
+        if self.reverse_lookups {
+            let ips: Vec<IpAddr> = findings
+                .iter()
+                .filter_map(|finding| match finding {
+                    Finding::DnsRecord { record_type, value, .. } if record_type == "A" || record_type == "AAAA" => {
+                        value.parse().ok()
+                    }
+                    _ => None,
+                })
+                .collect();
+            for ip in ips {
+                if let Ok(ptr) = self.lookup_ptr(ip).await {
+                    findings.extend(ptr);
+                }
+            }
         }
 
-        if let Ok(ns) = self.lookup_ns(domain).await {
-            metadata["ns_records"] = serde_json::json!(ns);
+        // Try a zone transfer against each authoritative name server
+        let nameservers: Vec<String> = findings
+            .iter()
+            .filter_map(|finding| match finding {
+                Finding::DnsRecord { record_type, value, .. } if record_type == "NS" => Some(value.clone()),
+                _ => None,
+            })
+            .collect();
+        let mut transfers = Vec::new();
+        let mut zone = Vec::new();
+        let mut seen: HashSet<Finding> = HashSet::new();
+        for (nameserver, ip, outcome) in self.transfer_zone(domain, nameservers, config).await {
+            findings.push(Finding::ZoneTransfer {
+                nameserver: nameserver.clone(),
+                ip,
+                allowed: outcome.is_ok(),
+            });
+            match outcome {
+                Ok(records) => {
+                    transfers.push(serde_json::json!({
+                        "nameserver": nameserver, "ip": ip, "allowed": true, "records": records.len(),
+                    }));
+                    // Servers of the same zone return the same records, keep each one once
+                    for finding in records.iter().filter_map(record_finding) {
+                        if seen.insert(finding.clone()) {
+                            zone.push(finding);
+                        }
+                    }
+                }
+                Err(e) => transfers.push(serde_json::json!({
+                    "nameserver": nameserver, "ip": ip, "allowed": false, "error": e,
+                })),
+            }
         }
 
-        if let Ok(txt) = self.lookup_txt(domain).await {
-            metadata["txt_records"] = serde_json::json!(txt);
+        // Group the values by record type, e.g. `metadata["mx_records"]`
+        let mut metadata = serde_json::json!({});
+        for finding in &findings {
+            if let Finding::DnsRecord { record_type, value, .. } = finding {
+                let key = format!("{}_records", record_type.to_lowercase());
+                match metadata[&key].as_array_mut() {
+                    Some(values) => values.push(serde_json::json!(value)),
+                    None => metadata[&key] = serde_json::json!([value]),
+                }
+            }
         }
 
+        metadata["zone_transfers"] = serde_json::json!(transfers);
+        findings.extend(zone);
+
         Ok(ModuleResult {
             source: "DNS".to_string(),
-            data,
+            findings,
             metadata: Some(metadata),
         })
     }
-} 
\ No newline at end of file
+}
+
+/// Requests `domain`'s zone from the name server at `ip` over TCP and collects
+/// the records up to the closing SOA.
+async fn axfr(domain: &str, ip: IpAddr, wait: Duration) -> Result<Vec<Record>, Box<dyn Error>> {
+    let mut request = Message::new();
+    request
+        .set_id(std::process::id() as u16)
+        .set_recursion_desired(false)
+        .add_query(Query::query(Name::from_ascii(domain)?, RecordType::AXFR));
+    let request = request.to_vec()?;
+
+    let mut stream = timeout(wait, TcpStream::connect((ip, 53))).await??;
+    stream.write_all(&(request.len() as u16).to_be_bytes()).await?;
+    stream.write_all(&request).await?;
+
+    read_transfer(&mut stream, wait).await
+}
+
+/// Reads the length-prefixed messages of an AXFR response from `stream` and
+/// collects the records up to the closing SOA.
+async fn read_transfer(
+    stream: &mut (impl AsyncRead + Unpin),
+    wait: Duration,
+) -> Result<Vec<Record>, Box<dyn Error>> {
+    let mut records: Vec<Record> = Vec::new();
+    let mut soa_count = 0;
+    while soa_count < 2 {
+        let mut length = [0u8; 2];
+        timeout(wait, stream.read_exact(&mut length)).await??;
+        let mut buffer = vec![0u8; u16::from_be_bytes(length) as usize];
+        timeout(wait, stream.read_exact(&mut buffer)).await??;
+
+        let response = Message::from_vec(&buffer)?;
+        if response.response_code() != ResponseCode::NoError {
+            return Err(format!("Transfer refused ({})", response.response_code()).into());
+        }
+        if response.answers().is_empty() {
+            return Err("Transfer refused (empty answer)".into());
+        }
+        if records.is_empty() && response.answers()[0].record_type() != RecordType::SOA {
+            return Err("Transfer refused (answer does not start with SOA)".into());
+        }
+        for record in response.answers() {
+            if record.record_type() == RecordType::SOA {
+                soa_count += 1;
+            }
+            // The zone ends with a repeat of its SOA
+            if soa_count < 2 {
+                records.push(record.clone());
+            }
+        }
+    }
+
+    Ok(records)
+}
+
+/// Findings of the answers of type `record_type`, leaving out the CNAMEs and
+/// other records a resolver returns along the way.
+fn answer_findings<'a>(
+    records: impl Iterator<Item = &'a Record>,
+    record_type: RecordType,
+) -> Vec<Finding> {
+    records
+        .filter(|record| record.record_type() == record_type)
+        .filter_map(record_finding)
+        .collect()
+}
+
+fn record_finding(record: &Record) -> Option<Finding> {
+    let data = record.data()?;
+    let name = record.name().to_string().trim_end_matches('.').to_string();
+    Some(Finding::DnsRecord {
+        record_type: record_label(&name, record.record_type(), data),
+        value: record_value(data).trim_end_matches('.').to_string(),
+        ttl: Some(record.ttl()),
+        name,
+    })
+}
+
+/// Record type reported for a finding; TXT records carrying SPF, DMARC or DKIM
+/// policies are reported under those names.
+fn record_label(name: &str, record_type: RecordType, data: &RData) -> String {
+    if let RData::TXT(txt) = data {
+        let value = txt.to_string();
+        if name.contains("._domainkey.") {
+            return "DKIM".to_string();
+        }
+        if name.starts_with("_dmarc.") && value.starts_with("v=DMARC1") {
+            return "DMARC".to_string();
+        }
+        if value.starts_with("v=spf1") {
+            return "SPF".to_string();
+        }
+    }
+    record_type.to_string()
+}
+
+/// Presentation format of a record's data. DNSKEY and DS are not decoded by the
+/// resolver without DNSSEC support, so their wire format is formatted here.
+fn record_value(data: &RData) -> String {
+    match data {
+        RData::Unknown { code, rdata } => {
+            let bytes = rdata.anything();
+            match RecordType::from(*code) {
+                // flags(2) protocol(1) algorithm(1) public key
+                RecordType::DNSKEY if bytes.len() > 4 => format!(
+                    "{} {} {} {}",
+                    u16::from_be_bytes([bytes[0], bytes[1]]),
+                    bytes[2],
+                    bytes[3],
+                    base64::engine::general_purpose::STANDARD.encode(&bytes[4..])
+                ),
+                // key tag(2) algorithm(1) digest type(1) digest
+                RecordType::DS if bytes.len() > 4 => format!(
+                    "{} {} {} {}",
+                    u16::from_be_bytes([bytes[0], bytes[1]]),
+                    bytes[2],
+                    bytes[3],
+                    hex::encode_upper(&bytes[4..])
+                ),
+                _ => rdata.to_string(),
+            }
+        }
+        data => data.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trust_dns_resolver::proto::op::MessageType;
+    use trust_dns_resolver::proto::rr::rdata::{A, NULL, SOA, TXT};
+
+    fn record(name: &str, rdata: RData) -> Record {
+        Record::from_rdata(Name::from_ascii(name).unwrap(), 300, rdata)
+    }
+
+    fn txt(name: &str, value: &str) -> Record {
+        record(name, RData::TXT(TXT::new(vec![value.to_string()])))
+    }
+
+    fn soa() -> Record {
+        let name = Name::from_ascii("ns1.example.com.").unwrap();
+        let admin = Name::from_ascii("hostmaster.example.com.").unwrap();
+        let soa = SOA::new(name, admin, 2024010101, 7200, 3600, 1209600, 300);
+        record("example.com.", RData::SOA(soa))
+    }
+
+    /// A record of `record_type` whose data the resolver left undecoded, as it
+    /// does for DNSSEC types without the `dnssec` feature. Built by hand since
+    /// trust-dns debug-asserts on decoding these off the wire.
+    fn unknown(name: &str, record_type: RecordType, bytes: &[u8]) -> Record {
+        let rdata = RData::Unknown {
+            code: record_type.into(),
+            rdata: NULL::with(bytes.to_vec()),
+        };
+        let mut record = record(name, rdata);
+        record.set_record_type(record_type);
+        record
+    }
+
+    fn value(finding: &Finding) -> (&str, &str) {
+        match finding {
+            Finding::DnsRecord {
+                record_type, value, ..
+            } => (record_type.as_str(), value.as_str()),
+            finding => panic!("not a DNS record: {}", finding),
+        }
+    }
+
+    #[test]
+    fn labels_mail_policy_txt_records() {
+        let records = [
+            txt("example.com.", "v=spf1 include:_spf.example.net -all"),
+            txt("_dmarc.example.com.", "v=DMARC1; p=reject"),
+            txt("selector1._domainkey.example.com.", "v=DKIM1; k=rsa; p=MIGf"),
+            txt("example.com.", "google-site-verification=abc"),
+        ];
+        let labels: Vec<String> = records
+            .iter()
+            .filter_map(record_finding)
+            .map(|finding| value(&finding).0.to_string())
+            .collect();
+
+        assert_eq!(labels, ["SPF", "DMARC", "DKIM", "TXT"]);
+    }
+
+    #[test]
+    fn decodes_undecoded_dnskey_and_ds_records() {
+        let records = [
+            unknown("example.com.", RecordType::DNSKEY, &[0x01, 0x01, 3, 8, 0xde, 0xad]),
+            unknown("example.com.", RecordType::DS, &[0x30, 0x39, 8, 2, 0xab, 0xcd]),
+            record("www.example.com.", RData::A(A::new(192, 0, 2, 80))),
+        ];
+
+        let dnskeys = answer_findings(records.iter(), RecordType::DNSKEY);
+        assert_eq!(dnskeys.len(), 1);
+        assert_eq!(value(&dnskeys[0]), ("DNSKEY", "257 3 8 3q0="));
+
+        let ds = answer_findings(records.iter(), RecordType::DS);
+        assert_eq!(value(&ds[0]), ("DS", "12345 8 2 ABCD"));
+    }
+
+    /// Length-prefixes each of `messages` as on a DNS TCP stream.
+    fn framed(messages: &[Vec<Record>], response_code: ResponseCode) -> Vec<u8> {
+        let mut stream = Vec::new();
+        for answers in messages {
+            let mut message = Message::new();
+            message
+                .set_message_type(MessageType::Response)
+                .set_response_code(response_code)
+                .add_answers(answers.clone());
+            let bytes = message.to_vec().unwrap();
+            stream.extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+            stream.extend_from_slice(&bytes);
+        }
+        stream
+    }
+
+    #[tokio::test]
+    async fn reads_transfers_up_to_the_closing_soa() {
+        let wait = Duration::from_secs(1);
+        let stream = framed(
+            &[
+                vec![soa(), record("www.example.com.", RData::A(A::new(192, 0, 2, 80)))],
+                vec![txt("example.com.", "v=spf1 -all"), soa()],
+                vec![record("late.example.com.", RData::A(A::new(192, 0, 2, 99)))],
+            ],
+            ResponseCode::NoError,
+        );
+
+        let records = read_transfer(&mut stream.as_slice(), wait).await.unwrap();
+        let types: Vec<RecordType> = records.iter().map(Record::record_type).collect();
+        assert_eq!(types, [RecordType::SOA, RecordType::A, RecordType::TXT]);
+
+        let refused = framed(&[vec![]], ResponseCode::Refused);
+        let error = read_transfer(&mut refused.as_slice(), wait).await.unwrap_err();
+        assert!(error.to_string().starts_with("Transfer refused"));
+    }
+}
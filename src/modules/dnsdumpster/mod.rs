@@ -0,0 +1,229 @@
+use crate::config::Config;
+use crate::findings::Finding;
+use crate::http::HttpClient;
+use crate::modules::{Category, Footprint, Module, ModuleResult};
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::header::SET_COOKIE;
+use select::document::Document;
+use select::node::Node;
+use select::predicate::{Class, Name, Predicate};
+use serde::Serialize;
+use serde_json::json;
+use std::error::Error;
+use std::net::IpAddr;
+
+const DNSDUMPSTER_URL: &str = "https://dnsdumpster.com/";
+
+pub struct DnsDumpsterModule {
+    client: HttpClient,
+}
+
+impl DnsDumpsterModule {
+    pub fn new(client: HttpClient) -> Self {
+        Self { client }
+    }
+
+    async fn search(&self, domain: &str) -> Result<String, Box<dyn Error>> {
+        // The landing page hands out the CSRF token both as a cookie and as a form field
+        let request = self.client
+            .get(DNSDUMPSTER_URL)
+            .header("Referer", "https://dnsdumpster.com");
+        let response = self.client.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(format!("Unexpected status code: {}", response.status()).into());
+        }
+
+        let cookies = response
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(|value| value.split(';').next())
+            .collect::<Vec<&str>>()
+            .join("; ");
+
+        let text = response.text().await?;
+        let token = extract_csrf_token(&text).ok_or("CSRF token not found")?;
+
+        let request = self.client
+            .post(DNSDUMPSTER_URL)
+            .header("Referer", "https://dnsdumpster.com")
+            .header("Cookie", cookies)
+            .form(&[("csrfmiddlewaretoken", token.as_str()), ("targetip", domain)]);
+        let response = self.client.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(format!("Unexpected status code: {}", response.status()).into());
+        }
+
+        Ok(response.text().await?)
+    }
+}
+
+fn extract_csrf_token(html: &str) -> Option<String> {
+    let re = Regex::new(r#"(?s)name="csrfmiddlewaretoken" value="(.*?)""#).ok()?;
+    re.captures(html)
+        .and_then(|cap| cap.get(1))
+        .map(|token| token.as_str().to_string())
+}
+
+/// A row of one of DNSdumpster's result tables.
+#[derive(Debug, Serialize)]
+struct Row {
+    /// Host name, MX exchange (with preference) or TXT value
+    value: String,
+    ip: Option<IpAddr>,
+    /// ASN and network owner as shown next to the address
+    network: Option<String>,
+}
+
+/// Text of a cell up to its first `<br>`, i.e. without the links and notes below it.
+fn cell_text(cell: Node) -> String {
+    let mut text = String::new();
+    for child in cell.children() {
+        if child.is(Name("br")) {
+            break;
+        }
+        text.push_str(&child.text());
+    }
+    text.trim().trim_end_matches('.').to_string()
+}
+
+/// Headings that introduce the DNS server, MX, TXT and host record tables.
+const SECTIONS: [&str; 4] = ["DNS Servers", "MX Records", "TXT Records", "Host Records"];
+
+/// Parses the DNS server, MX, TXT and host record tables, each picked by the
+/// heading before it so that a section missing from the page leaves its slot empty.
+fn extract_tables(html: &str) -> [Vec<Row>; 4] {
+    let document = Document::from(html);
+    let mut tables: [Vec<Row>; 4] = Default::default();
+    let mut section = None;
+
+    for node in document.find(Name("p").or(Name("table").and(Class("table")))) {
+        if !node.is(Name("table")) {
+            let heading = node.text();
+            section = SECTIONS.iter().position(|title| heading.trim().starts_with(title));
+            continue;
+        }
+        let Some(index) = section.take() else {
+            continue;
+        };
+
+        for row in node.find(Name("tr")) {
+            let cells: Vec<Node> = row.find(Name("td")).collect();
+            let Some(first) = cells.first() else {
+                continue;
+            };
+            let value = cell_text(*first);
+            if value.is_empty() {
+                continue;
+            }
+            tables[index].push(Row {
+                value,
+                ip: cells.get(1).and_then(|cell| cell_text(*cell).parse().ok()),
+                network: cells
+                    .get(2)
+                    .map(|cell| cell_text(*cell))
+                    .filter(|network| !network.is_empty()),
+            });
+        }
+    }
+
+    tables
+}
+
+#[async_trait]
+impl Module for DnsDumpsterModule {
+    fn name(&self) -> &'static str {
+        "dnsdump"
+    }
+
+    fn description(&self) -> &'static str {
+        "DNSDumpster DNS, MX, TXT and host records module"
+    }
+
+    fn category(&self) -> Category {
+        Category::Dns
+    }
+
+    fn footprint(&self) -> Footprint {
+        Footprint::Passive
+    }
+
+    async fn run(&self, domain: &str, _config: &Config) -> Result<ModuleResult, Box<dyn Error>> {
+        let results = self.search(domain).await?;
+        let [dns, mx, txt, hosts] = extract_tables(&results);
+        let mut findings = Vec::new();
+
+        for (record_type, rows) in [("NS", &dns), ("MX", &mx)] {
+            for row in rows {
+                findings.push(Finding::DnsRecord {
+                    name: domain.to_string(),
+                    record_type: record_type.to_string(),
+                    value: row.value.clone(),
+                    ttl: None,
+                });
+                if let Some(ip) = row.ip {
+                    let hostname = row.value.split_whitespace().last().unwrap_or_default();
+                    findings.push(Finding::Resolution {
+                        hostname: hostname.to_string(),
+                        ip,
+                    });
+                }
+            }
+        }
+
+        findings.extend(txt.iter().map(|row| Finding::DnsRecord {
+            name: domain.to_string(),
+            record_type: "TXT".to_string(),
+            value: row.value.trim_matches('"').to_string(),
+            ttl: None,
+        }));
+
+        for row in &hosts {
+            match row.ip {
+                Some(ip) => findings.push(Finding::DnsRecord {
+                    name: row.value.clone(),
+                    record_type: if ip.is_ipv4() { "A" } else { "AAAA" }.to_string(),
+                    value: ip.to_string(),
+                    ttl: None,
+                }),
+                None => findings.push(Finding::hostname(&row.value)),
+            }
+        }
+
+        Ok(ModuleResult {
+            source: "DNSdumpster".to_string(),
+            findings,
+            metadata: Some(json!({
+                "dns_records": dns,
+                "mx_records": mx,
+                "txt_records": txt,
+                "host_records": hosts,
+            })),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_tables_by_heading_when_a_section_is_missing() {
+        let [dns, mx, txt, hosts] = extract_tables(include_str!("testdata/no_mx.html"));
+
+        assert_eq!(dns.len(), 2);
+        assert_eq!(dns[0].value, "ns1.example.com");
+        assert_eq!(dns[0].ip, Some("192.0.2.53".parse().unwrap()));
+        assert_eq!(dns[0].network.as_deref(), Some("AS64500 EXAMPLE-NET"));
+        assert!(mx.is_empty());
+        assert_eq!(txt.len(), 1);
+        assert_eq!(txt[0].value, "\"v=spf1 -all\"");
+        assert_eq!(hosts.len(), 2);
+        assert_eq!(hosts[1].value, "www.example.com");
+        assert_eq!(hosts[1].ip, Some("192.0.2.80".parse().unwrap()));
+    }
+}
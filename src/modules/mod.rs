@@ -1,6 +1,14 @@
+use crate::findings::Finding;
+use crate::http::{HttpClient, RateLimiters};
 use async_trait::async_trait;
+use futures::future::{join_all, BoxFuture};
 use serde::{Deserialize, Serialize};
 use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use trust_dns_resolver::config::{ResolverConfig, ResolverOpts};
+use trust_dns_resolver::TokioAsyncResolver;
 
 pub mod dns;
 pub mod whois;
@@ -9,79 +17,256 @@ pub mod shodan;
 pub mod censys;
 pub mod spyse;
 pub mod vhosts;
+pub mod revdns;
+pub mod brute;
+pub mod social;
+pub mod crt;
+pub mod pgp;
+pub mod netcraft;
+pub mod virustotal;
+pub mod dnsdumpster;
+
+/// Network clients shared by every module, built once from the configuration.
+/// `permits` bounds the HTTP requests and DNS lookups in flight across all modules.
+#[derive(Clone)]
+pub struct Services {
+    pub client: HttpClient,
+    pub resolver: TokioAsyncResolver,
+    pub permits: Arc<Semaphore>,
+    pub limiters: RateLimiters,
+}
+
+impl Services {
+    pub fn from_config(config: &crate::config::Config) -> Result<Self, Box<dyn Error>> {
+        let permits = Arc::new(Semaphore::new(config.concurrency));
+        Ok(Self {
+            client: crate::http::build_client(config, permits.clone())?,
+            resolver: crate::resolver::build_resolver(config)?,
+            permits,
+            limiters: RateLimiters::new(),
+        })
+    }
+
+    /// Default clients that ignore the configuration, for instantiating modules
+    /// to inspect them (e.g. `--list-modules`). Nothing connects until a module runs.
+    pub fn unconfigured() -> Self {
+        Self {
+            client: reqwest::Client::new().into(),
+            resolver: TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default()),
+            permits: Arc::new(Semaphore::new(1)),
+            limiters: RateLimiters::new(),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModuleResult {
     pub source: String,
-    pub data: Vec<String>,
+    pub findings: Vec<Finding>,
     pub metadata: Option<serde_json::Value>,
 }
 
+/// API credentials a module needs before it can run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Credential {
+    ShodanApiKey,
+    SpyseApiKey,
+    CensysApiId,
+    CensysApiSecret,
+    VirusTotalApiKey,
+}
+
+impl Credential {
+    pub fn is_configured(&self, config: &crate::config::Config) -> bool {
+        match self {
+            Credential::ShodanApiKey => config.shodan_api_key.is_some(),
+            Credential::SpyseApiKey => config.spyse_api_key.is_some(),
+            Credential::CensysApiId => config.censys_api_id.is_some(),
+            Credential::CensysApiSecret => config.censys_api_secret.is_some(),
+            Credential::VirusTotalApiKey => config.virustotal_api_key.is_some(),
+        }
+    }
+
+    /// Command line flag that supplies the credential.
+    pub fn flag(&self) -> &'static str {
+        match self {
+            Credential::ShodanApiKey => "--shodan-key",
+            Credential::SpyseApiKey => "--spyse-key",
+            Credential::CensysApiId => "--censys-api-id",
+            Credential::CensysApiSecret => "--censys-api-secret",
+            Credential::VirusTotalApiKey => "--virustotal-key",
+        }
+    }
+}
+
+impl fmt::Display for Credential {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Credential::ShodanApiKey => "Shodan API key",
+            Credential::SpyseApiKey => "Spyse API key",
+            Credential::CensysApiId => "Censys API ID",
+            Credential::CensysApiSecret => "Censys API secret",
+            Credential::VirusTotalApiKey => "VirusTotal API key",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Whether a module only talks to third parties or also touches the target's infrastructure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Footprint {
+    Passive,
+    Active,
+}
+
+impl fmt::Display for Footprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Footprint::Passive => write!(f, "passive"),
+            Footprint::Active => write!(f, "active"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Composite,
+    Whois,
+    Dns,
+    VirtualHosts,
+    SearchEngine,
+    SocialNetwork,
+    Certificates,
+    Keyserver,
+    SubdomainSearch,
+    HostSearch,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Category::Composite => "composite",
+            Category::Whois => "whois",
+            Category::Dns => "dns",
+            Category::VirtualHosts => "vhosts",
+            Category::SearchEngine => "search engine",
+            Category::SocialNetwork => "social network",
+            Category::Certificates => "certificates",
+            Category::Keyserver => "keyserver",
+            Category::SubdomainSearch => "subdomain search",
+            Category::HostSearch => "host search",
+        };
+        write!(f, "{}", label)
+    }
+}
+
 #[async_trait]
 pub trait Module: Send + Sync {
     fn name(&self) -> &'static str;
     fn description(&self) -> &'static str;
+    fn category(&self) -> Category;
+    fn footprint(&self) -> Footprint;
+
+    fn credentials(&self) -> &'static [Credential] {
+        &[]
+    }
+
+    /// Credentials this module requires that are not present in `config`.
+    fn missing_credentials(&self, config: &crate::config::Config) -> Vec<Credential> {
+        self.credentials()
+            .iter()
+            .filter(|credential| !credential.is_configured(config))
+            .copied()
+            .collect()
+    }
+
     async fn run(&self, domain: &str, config: &crate::config::Config) -> Result<ModuleResult, Box<dyn Error>>;
 }
 
+type Constructor = fn(&Services) -> Box<dyn Module>;
+
+/// Every module that can be selected with `-i`, in the order they are listed.
+const REGISTRY: &[(&str, Constructor)] = &[
+    ("basic", |services| Box::new(BasicModule::new(services))),
+    ("nongoogle", |services| Box::new(NonGoogleModule::new(services))),
+    ("whois", |_| Box::new(whois::WhoisModule::new())),
+    ("dns", |services| Box::new(dns::DnsModule::new(services.resolver.clone(), services.permits.clone()))),
+    ("revdns", |services| Box::new(revdns::ReverseDnsModule::new(services.resolver.clone(), services.permits.clone()))),
+    ("brute", |services| Box::new(brute::BruteForceModule::new(services.resolver.clone(), services.permits.clone()))),
+    ("vhosts", |services| Box::new(vhosts::VhostsModule::new(services.client.clone()))),
+    ("search", |services| Box::new(search::SearchModule::new(services.client.clone()))),
+    ("google", |services| Box::new(search::SearchEngineModule::new(services.client.clone(), search::Google))),
+    ("bing", |services| Box::new(search::SearchEngineModule::new(services.client.clone(), search::Bing))),
+    ("yahoo", |services| Box::new(search::SearchEngineModule::new(services.client.clone(), search::Yahoo))),
+    ("ask", |services| Box::new(search::SearchEngineModule::new(services.client.clone(), search::Ask))),
+    ("dogpile", |services| Box::new(search::SearchEngineModule::new(services.client.clone(), search::Dogpile))),
+    ("yandex", |services| Box::new(search::SearchEngineModule::new(services.client.clone(), search::Yandex))),
+    ("linkedin", |services| Box::new(social::SocialModule::new(services.client.clone(), social::Network::LinkedIn))),
+    ("twitter", |services| Box::new(social::SocialModule::new(services.client.clone(), social::Network::Twitter))),
+    ("youtube", |services| Box::new(social::SocialModule::new(services.client.clone(), social::Network::YouTube))),
+    ("reddit", |services| Box::new(social::SocialModule::new(services.client.clone(), social::Network::Reddit))),
+    ("github", |services| Box::new(search::SearchEngineModule::new(services.client.clone(), search::GitHub))),
+    ("instagram", |services| Box::new(social::SocialModule::new(services.client.clone(), social::Network::Instagram))),
+    ("crt", |services| Box::new(crt::CrtModule::new(services.client.clone()))),
+    ("pgp", |services| Box::new(pgp::PgpModule::new(services.client.clone()))),
+    ("netcraft", |services| Box::new(netcraft::NetcraftModule::new(services.client.clone()))),
+    ("virustotal", |services| Box::new(virustotal::VirusTotalModule::new(services.client.clone(), services.limiters.virustotal.clone()))),
+    ("dnsdump", |services| Box::new(dnsdumpster::DnsDumpsterModule::new(services.client.clone()))),
+    ("shodan", |services| Box::new(shodan::ShodanModule::new(services.client.clone(), services.limiters.shodan.clone()))),
+    ("censys", |services| Box::new(censys::CensysModule::new(services.client.clone(), services.limiters.censys.clone()))),
+    ("spyse", |services| Box::new(spyse::SpyseModule::new(services.client.clone(), services.limiters.spyse.clone()))),
+];
+
 pub fn get_available_modules() -> Vec<&'static str> {
-    vec![
-        "basic",
-        "nongoogle",
-        "whois",
-        "dns",
-        "revdns",
-        "vhosts",
-        "google",
-        "bing",
-        "yahoo",
-        "ask",
-        "dogpile",
-        "yandex",
-        "linkedin",
-        "twitter",
-        "youtube",
-        "reddit",
-        "github",
-        "instagram",
-        "crt",
-        "pgp",
-        "netcraft",
-        "virustotal",
-        "dnsdump",
-        "shodan",
-        "censys",
-        "spyse",
-    ]
-}
-
-pub fn get_module_by_name(name: &str) -> Option<Box<dyn Module>> {
-    match name {
-        "whois" => Some(Box::new(whois::WhoisModule::new())),
-        "dns" => Some(Box::new(dns::DnsModule::new())),
-        "shodan" => Some(Box::new(shodan::ShodanModule::new())),
-        "censys" => Some(Box::new(censys::CensysModule::new())),
-        "spyse" => Some(Box::new(spyse::SpyseModule::new())),
-        "vhosts" => Some(Box::new(vhosts::VhostsModule::new())),
-        "search" => Some(Box::new(search::SearchModule::new())),
-        "basic" => Some(Box::new(BasicModule::new())),
-        "nongoogle" => Some(Box::new(NonGoogleModule::new())),
-        _ => None,
-    }
+    REGISTRY.iter().map(|(name, _)| *name).collect()
+}
+
+/// Instantiates the module registered as `name`, sharing the HTTP client and DNS resolver of `services`.
+pub fn get_module_by_name(name: &str, services: &Services) -> Option<Box<dyn Module>> {
+    REGISTRY
+        .iter()
+        .find(|(registered, _)| *registered == name)
+        .map(|(_, constructor)| constructor(services))
+}
+
+/// Instantiates every registered module, e.g. to list their metadata.
+pub fn get_all_modules(services: &Services) -> Vec<Box<dyn Module>> {
+    REGISTRY.iter().map(|(_, constructor)| constructor(services)).collect()
+}
+
+/// Runs `modules` side by side and returns their outcomes in the same order as
+/// `modules`; their network traffic is bounded by the shared `Services` permits.
+pub async fn run_concurrently<'a>(
+    modules: Vec<&'a dyn Module>,
+    domain: &'a str,
+    config: &'a crate::config::Config,
+) -> Vec<Result<ModuleResult, String>> {
+    // Boxing up front keeps the futures free of higher-ranked closure lifetimes,
+    // which would otherwise stop the surrounding future from being `Send`.
+    let runs: Vec<BoxFuture<'a, Result<ModuleResult, String>>> = modules
+        .into_iter()
+        .map(|module| -> BoxFuture<'a, Result<ModuleResult, String>> {
+            Box::pin(async move { module.run(domain, config).await.map_err(|e| e.to_string()) })
+        })
+        .collect();
+
+    join_all(runs).await
 }
 
 pub struct BasicModule {
     whois: whois::WhoisModule,
     dns: dns::DnsModule,
+    revdns: revdns::ReverseDnsModule,
     vhosts: vhosts::VhostsModule,
 }
 
 impl BasicModule {
-    pub fn new() -> Self {
+    pub fn new(services: &Services) -> Self {
         Self {
             whois: whois::WhoisModule::new(),
-            dns: dns::DnsModule::new(),
-            vhosts: vhosts::VhostsModule::new(),
+            dns: dns::DnsModule::without_reverse_lookups(services.resolver.clone(), services.permits.clone()),
+            revdns: revdns::ReverseDnsModule::new(services.resolver.clone(), services.permits.clone()),
+            vhosts: vhosts::VhostsModule::new(services.client.clone()),
         }
     }
 }
@@ -96,37 +281,33 @@ impl Module for BasicModule {
         "Basic information gathering module"
     }
 
+    fn category(&self) -> Category {
+        Category::Composite
+    }
+
+    fn footprint(&self) -> Footprint {
+        Footprint::Active
+    }
+
     async fn run(&self, domain: &str, config: &crate::config::Config) -> Result<ModuleResult, Box<dyn Error>> {
-        let mut data = Vec::new();
+        let mut findings = Vec::new();
         let mut metadata = serde_json::json!({});
 
-        // Run WHOIS
-        if let Ok(result) = self.whois.run(domain, config).await {
-            data.extend(result.data);
-            if let Some(whois_metadata) = result.metadata {
-                metadata["whois"] = whois_metadata;
-            }
-        }
-
-        // Run DNS
-        if let Ok(result) = self.dns.run(domain, config).await {
-            data.extend(result.data);
-            if let Some(dns_metadata) = result.metadata {
-                metadata["dns"] = dns_metadata;
-            }
-        }
+        let children: Vec<&dyn Module> = vec![&self.whois, &self.dns, &self.revdns, &self.vhosts];
+        let names: Vec<&str> = children.iter().map(|module| module.name()).collect();
 
-        // Run VHosts
-        if let Ok(result) = self.vhosts.run(domain, config).await {
-            data.extend(result.data);
-            if let Some(vhosts_metadata) = result.metadata {
-                metadata["vhosts"] = vhosts_metadata;
+        for (name, outcome) in names.into_iter().zip(run_concurrently(children, domain, config).await) {
+            if let Ok(result) = outcome {
+                findings.extend(result.findings);
+                if let Some(child_metadata) = result.metadata {
+                    metadata[name] = child_metadata;
+                }
             }
         }
 
         Ok(ModuleResult {
             source: "Basic".to_string(),
-            data,
+            findings,
             metadata: Some(metadata),
         })
     }
@@ -138,10 +319,10 @@ pub struct NonGoogleModule {
 }
 
 impl NonGoogleModule {
-    pub fn new() -> Self {
+    pub fn new(services: &Services) -> Self {
         Self {
-            basic: BasicModule::new(),
-            search: search::SearchModule::new(),
+            basic: BasicModule::new(services),
+            search: search::SearchModule::new(services.client.clone()),
         }
     }
 }
@@ -156,30 +337,49 @@ impl Module for NonGoogleModule {
         "Non-Google information gathering module"
     }
 
+    fn category(&self) -> Category {
+        Category::Composite
+    }
+
+    fn footprint(&self) -> Footprint {
+        Footprint::Active
+    }
+
     async fn run(&self, domain: &str, config: &crate::config::Config) -> Result<ModuleResult, Box<dyn Error>> {
-        let mut data = Vec::new();
+        let mut findings = Vec::new();
         let mut metadata = serde_json::json!({});
 
-        // Run basic modules
-        if let Ok(result) = self.basic.run(domain, config).await {
-            data.extend(result.data);
-            if let Some(basic_metadata) = result.metadata {
-                metadata["basic"] = basic_metadata;
-            }
-        }
+        let children: Vec<&dyn Module> = vec![&self.basic, &self.search];
+        let names: Vec<&str> = children.iter().map(|module| module.name()).collect();
 
-        // Run search module
-        if let Ok(result) = self.search.run(domain, config).await {
-            data.extend(result.data);
-            if let Some(search_metadata) = result.metadata {
-                metadata["search"] = search_metadata;
+        for (name, outcome) in names.into_iter().zip(run_concurrently(children, domain, config).await) {
+            if let Ok(result) = outcome {
+                findings.extend(result.findings);
+                if let Some(child_metadata) = result.metadata {
+                    metadata[name] = child_metadata;
+                }
             }
         }
 
         Ok(ModuleResult {
             source: "Non-Google".to_string(),
-            data,
+            findings,
             metadata: Some(metadata),
         })
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn every_advertised_module_resolves() {
+        let services = Services::unconfigured();
+        for name in get_available_modules() {
+            let module = get_module_by_name(name, &services)
+                .unwrap_or_else(|| panic!("advertised module '{}' does not resolve", name));
+            assert_eq!(module.name(), name);
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use crate::config::Config;
+use crate::findings::Finding;
+use crate::http::HttpClient;
+use crate::modules::{Category, Footprint, Module, ModuleResult};
+use crate::utils;
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::header::SET_COOKIE;
+use serde_json::json;
+use std::error::Error;
+
+const NETCRAFT_URL: &str = "https://searchdns.netcraft.com";
+
+/// Netcraft shows 20 results per page.
+const RESULTS_PER_PAGE: u32 = 20;
+
+pub struct NetcraftModule {
+    client: HttpClient,
+    base_url: String,
+}
+
+impl NetcraftModule {
+    pub fn new(client: HttpClient) -> Self {
+        Self::with_base_url(client, NETCRAFT_URL)
+    }
+
+    /// Searches a Netcraft-compatible site other than searchdns.netcraft.com.
+    pub fn with_base_url(client: HttpClient, base_url: &str) -> Self {
+        Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    /// Answers Netcraft's JavaScript challenge: the page sets
+    /// `netcraft_js_verification_challenge` and expects the SHA-1 of its
+    /// URL-decoded value back as `netcraft_js_verification_response`.
+    async fn get_cookies(&self, url: &str) -> Result<String, Box<dyn Error>> {
+        let request = self.client.get(url);
+        let response = self.client.send(request).await?;
+
+        let mut cookies: Vec<String> = response
+            .headers()
+            .get_all(SET_COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .filter_map(|value| value.split(';').next())
+            .map(String::from)
+            .collect();
+
+        let challenge = cookies
+            .iter()
+            .find_map(|cookie| cookie.strip_prefix("netcraft_js_verification_challenge="))
+            .map(|challenge| urlencoding::decode(challenge).map(|c| c.into_owned()))
+            .transpose()?;
+        if let Some(challenge) = challenge {
+            cookies.push(format!(
+                "netcraft_js_verification_response={}",
+                utils::sha1_hex(challenge.as_bytes())
+            ));
+        }
+
+        Ok(cookies.join("; "))
+    }
+
+    async fn search(&self, domain: &str, limit: u32) -> Result<String, Box<dyn Error>> {
+        let mut url = format!(
+            "{}/?restriction=site+ends+with&host={}",
+            self.base_url,
+            urlencoding::encode(domain)
+        );
+        let cookies = self.get_cookies(&url).await?;
+        let next_page = Regex::new(r#"(?i)<a href="([^"]*)"><b>Next page</b></a>"#)?;
+
+        let mut results = String::new();
+        let mut fetched = 0;
+        loop {
+            let request = self.client
+                .get(&url)
+                .header("Cookie", &cookies);
+            let response = self.client.send(request).await?;
+
+            if !response.status().is_success() {
+                return Err(format!("Unexpected status code: {}", response.status()).into());
+            }
+            let text = response.text().await?;
+            fetched += RESULTS_PER_PAGE;
+
+            let next = next_page
+                .captures(&text)
+                .and_then(|cap| cap.get(1))
+                .map(|link| format!("{}{}", self.base_url, link.as_str().replace("&amp;", "&")));
+            results.push_str(&text);
+
+            match next {
+                Some(next) if fetched < limit => url = next,
+                _ => break,
+            }
+
+            self.client.pause().await;
+        }
+
+        Ok(results)
+    }
+}
+
+#[async_trait]
+impl Module for NetcraftModule {
+    fn name(&self) -> &'static str {
+        "netcraft"
+    }
+
+    fn description(&self) -> &'static str {
+        "Netcraft subdomain search module"
+    }
+
+    fn category(&self) -> Category {
+        Category::SubdomainSearch
+    }
+
+    fn footprint(&self) -> Footprint {
+        Footprint::Passive
+    }
+
+    async fn run(&self, domain: &str, config: &Config) -> Result<ModuleResult, Box<dyn Error>> {
+        let results = self.search(domain, config.limit).await?;
+        let hostnames = utils::get_hostnames(&results, domain);
+
+        Ok(ModuleResult {
+            source: "Netcraft".to_string(),
+            findings: hostnames.iter().map(Finding::hostname).collect(),
+            metadata: Some(json!({ "hostnames": hostnames })),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::test_server::serve;
+    use clap::Parser;
+
+    fn html_response(headers: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 200 OK\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            headers,
+            body.len(),
+            body
+        )
+    }
+
+    #[tokio::test]
+    async fn follows_next_pages_and_extracts_hostnames() {
+        let challenge = html_response(
+            "Set-Cookie: netcraft_js_verification_challenge=abc%3D; path=/\r\n",
+            "<html></html>",
+        );
+        let first = html_response(
+            "",
+            r#"<table><tr><td><a href="http://www.example.com">www.example.com</a></td></tr>
+               <tr><td><a href="http://mail.example.com">mail.example.com</a></td></tr></table>
+               <a href="/?restriction=site+ends+with&amp;host=example.com&amp;last=mail.example.com"><b>Next page</b></a>"#,
+        );
+        let last = html_response(
+            "",
+            r#"<table><tr><td><a href="http://vpn.example.com">vpn.example.com</a></td></tr></table>"#,
+        );
+        let base_url = serve(&[&challenge, &first, &last]).await;
+
+        let config = Config::new(&crate::Args::parse_from(["gasmask"])).unwrap();
+        let module = NetcraftModule::with_base_url(reqwest::Client::new().into(), &base_url);
+        let result = module.run("example.com", &config).await.unwrap();
+
+        assert_eq!(
+            result.findings,
+            vec![
+                Finding::hostname("mail.example.com"),
+                Finding::hostname("vpn.example.com"),
+                Finding::hostname("www.example.com"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,186 @@
+use crate::config::Config;
+use crate::findings::Finding;
+use crate::http::HttpClient;
+use crate::modules::{Category, Footprint, Module, ModuleResult};
+use crate::utils;
+use async_trait::async_trait;
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::error::Error;
+
+/// Keyservers queried when no `--keyserver` is given.
+const DEFAULT_KEYSERVERS: &[&str] = &["https://keyserver.ubuntu.com", "https://pgp.mit.edu"];
+
+pub struct PgpModule {
+    client: HttpClient,
+}
+
+impl PgpModule {
+    pub fn new(client: HttpClient) -> Self {
+        Self { client }
+    }
+
+    /// Fetches the machine-readable HKP index of keys matching `domain`.
+    async fn search(&self, keyserver: &str, domain: &str) -> Result<String, Box<dyn Error>> {
+        let url = format!(
+            "{}/pks/lookup?search={}&op=index&options=mr",
+            keyserver.trim_end_matches('/'),
+            urlencoding::encode(domain)
+        );
+
+        let request = self.client.get(&url);
+        let response = self.client.send(request).await?;
+
+        // HKP servers answer 404 when nothing matches
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(String::new());
+        }
+        if !response.status().is_success() {
+            return Err(format!("Unexpected status code: {}", response.status()).into());
+        }
+
+        Ok(response.text().await?)
+    }
+}
+
+/// Parses an HKP `options=mr` index into (fingerprint or key ID, user ID) pairs.
+fn parse_index(index: &str) -> Vec<(String, String)> {
+    let mut keys = Vec::new();
+    let mut current_key: Option<String> = None;
+
+    for line in index.lines() {
+        let fields: Vec<&str> = line.trim().split(':').collect();
+        match fields.first() {
+            Some(&"pub") => current_key = fields.get(1).map(|key| key.to_uppercase()),
+            Some(&"uid") => {
+                if let (Some(key), Some(uid)) = (&current_key, fields.get(1)) {
+                    let uid = urlencoding::decode(uid)
+                        .map(|uid| uid.into_owned())
+                        .unwrap_or_else(|_| uid.to_string());
+                    keys.push((key.clone(), uid));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    keys
+}
+
+#[async_trait]
+impl Module for PgpModule {
+    fn name(&self) -> &'static str {
+        "pgp"
+    }
+
+    fn description(&self) -> &'static str {
+        "PGP keyserver (HKP) user ID, email and key harvesting module"
+    }
+
+    fn category(&self) -> Category {
+        Category::Keyserver
+    }
+
+    fn footprint(&self) -> Footprint {
+        Footprint::Passive
+    }
+
+    async fn run(&self, domain: &str, config: &Config) -> Result<ModuleResult, Box<dyn Error>> {
+        let keyservers: Vec<String> = if config.keyservers.is_empty() {
+            DEFAULT_KEYSERVERS.iter().map(|keyserver| keyserver.to_string()).collect()
+        } else {
+            config.keyservers.clone()
+        };
+
+        let mut keys = BTreeSet::new();
+        let mut errors = serde_json::Map::new();
+        for keyserver in &keyservers {
+            match self.search(keyserver, domain).await {
+                Ok(index) => keys.extend(parse_index(&index)),
+                Err(e) => {
+                    errors.insert(keyserver.clone(), json!(e.to_string()));
+                }
+            }
+        }
+        if errors.len() == keyservers.len() {
+            return Err(format!("No keyserver answered: {}", json!(errors)).into());
+        }
+
+        // Only keep user IDs with an address at the target domain
+        let mut findings = Vec::new();
+        let mut emails = BTreeSet::new();
+        let mut matching_keys = Vec::new();
+        for (fingerprint, user_id) in keys {
+            let addresses = utils::get_emails(&user_id, domain);
+            if addresses.is_empty() {
+                continue;
+            }
+            for email in addresses {
+                findings.push(Finding::PgpKey {
+                    fingerprint: fingerprint.clone(),
+                    user_id: user_id.clone(),
+                    email: email.clone(),
+                });
+                emails.insert(email);
+            }
+            matching_keys.push(json!({ "fingerprint": fingerprint, "user_id": user_id }));
+        }
+        findings.extend(emails.iter().map(Finding::email));
+
+        Ok(ModuleResult {
+            source: "PGP".to_string(),
+            findings,
+            metadata: Some(json!({
+                "keyservers": keyservers,
+                "keys": matching_keys,
+                "emails": emails,
+                "errors": errors,
+            })),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::test_server::serve;
+    use clap::Parser;
+
+    #[tokio::test]
+    async fn parses_machine_readable_indexes() {
+        let index = include_str!("testdata/index.txt");
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            index.len(),
+            index
+        );
+        let base_url = serve(&[&response]).await;
+
+        let mut config = Config::new(&crate::Args::parse_from(["gasmask"])).unwrap();
+        config.keyservers = vec![base_url];
+        let module = PgpModule::new(reqwest::Client::new().into());
+        let result = module.run("example.com", &config).await.unwrap();
+
+        assert_eq!(
+            result.findings,
+            vec![
+                Finding::PgpKey {
+                    fingerprint: "0123456789ABCDEF0123456789ABCDEF01234567".to_string(),
+                    user_id: "Jane Doe <jane@example.com>".to_string(),
+                    email: "jane@example.com".to_string(),
+                },
+                Finding::PgpKey {
+                    fingerprint: "FEDCBA9876543210FEDCBA9876543210FEDCBA98".to_string(),
+                    user_id: "Ops Team <ops@mail.example.com>".to_string(),
+                    email: "ops@mail.example.com".to_string(),
+                },
+                Finding::email("jane@example.com"),
+                Finding::email("ops@mail.example.com"),
+            ]
+        );
+        // The key without a user ID contributes nothing
+        assert!(!parse_index(index)
+            .iter()
+            .any(|(key, _)| key.starts_with("89ABCDEF")));
+    }
+}
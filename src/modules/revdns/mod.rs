@@ -0,0 +1,204 @@
+use crate::config::Config;
+use crate::findings::Finding;
+use crate::modules::{Category, Footprint, Module, ModuleResult};
+use async_trait::async_trait;
+use futures::future::{join_all, BoxFuture};
+use serde_json::json;
+use std::error::Error;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use trust_dns_resolver::TokioAsyncResolver;
+
+/// Largest number of addresses a single `--range` entry may expand to.
+const MAX_RANGE_SIZE: u128 = 65536;
+
+pub struct ReverseDnsModule {
+    resolver: TokioAsyncResolver,
+    permits: Arc<Semaphore>,
+}
+
+impl ReverseDnsModule {
+    pub fn new(resolver: TokioAsyncResolver, permits: Arc<Semaphore>) -> Self {
+        Self { resolver, permits }
+    }
+
+    async fn lookup_ips(&self, domain: &str) -> Result<Vec<IpAddr>, Box<dyn Error>> {
+        let _permit = self.permits.acquire().await?;
+        let response = self.resolver.lookup_ip(domain).await?;
+        Ok(response.iter().collect())
+    }
+
+    async fn lookup_ptr(&self, ip: IpAddr) -> Result<Vec<String>, Box<dyn Error>> {
+        let _permit = self.permits.acquire().await?;
+        let response = self.resolver.reverse_lookup(ip).await?;
+        Ok(response
+            .iter()
+            .map(|name| name.to_string().trim_end_matches('.').to_lowercase())
+            .collect())
+    }
+}
+
+#[async_trait]
+impl Module for ReverseDnsModule {
+    fn name(&self) -> &'static str {
+        "revdns"
+    }
+
+    fn description(&self) -> &'static str {
+        "Reverse DNS lookup of the domain's addresses or a --range of IPs/CIDRs"
+    }
+
+    fn category(&self) -> Category {
+        Category::Dns
+    }
+
+    fn footprint(&self) -> Footprint {
+        Footprint::Active
+    }
+
+    async fn run(&self, domain: &str, config: &Config) -> Result<ModuleResult, Box<dyn Error>> {
+        let mut findings = Vec::new();
+        let mut metadata = json!({});
+
+        let ips = if config.ip_ranges.is_empty() {
+            self.lookup_ips(domain).await?
+        } else {
+            let mut ips = Vec::new();
+            for range in &config.ip_ranges {
+                ips.extend(expand_range(range)?);
+            }
+            ips.sort();
+            ips.dedup();
+            ips
+        };
+
+        let lookups: Vec<BoxFuture<'_, (IpAddr, Vec<String>)>> = ips
+            .into_iter()
+            .map(|ip| -> BoxFuture<'_, (IpAddr, Vec<String>)> {
+                Box::pin(async move { (ip, self.lookup_ptr(ip).await.unwrap_or_default()) })
+            })
+            .collect();
+        let resolved = join_all(lookups).await;
+
+        let suffix = format!(".{}", domain.to_lowercase());
+        let mut in_domain = Vec::new();
+        let mut out_of_domain = Vec::new();
+        for (ip, names) in resolved {
+            for name in &names {
+                findings.push(Finding::DnsRecord {
+                    name: ip.to_string(),
+                    record_type: "PTR".to_string(),
+                    value: name.clone(),
+                    ttl: None,
+                });
+                let entry = json!({ "ip": ip, "hostname": name });
+                if *name == domain.to_lowercase() || name.ends_with(&suffix) {
+                    in_domain.push(entry);
+                } else {
+                    out_of_domain.push(entry);
+                }
+            }
+        }
+
+        metadata["in_domain"] = json!(in_domain);
+        metadata["out_of_domain"] = json!(out_of_domain);
+
+        Ok(ModuleResult {
+            source: "Reverse DNS".to_string(),
+            findings,
+            metadata: Some(metadata),
+        })
+    }
+}
+
+/// Expands a single IP address or an IPv4/IPv6 CIDR block into its addresses.
+fn expand_range(range: &str) -> Result<Vec<IpAddr>, Box<dyn Error>> {
+    let range = range.trim();
+    let (address, prefix) = match range.split_once('/') {
+        Some((address, prefix)) => (address, Some(prefix)),
+        None => (range, None),
+    };
+    let address: IpAddr = address
+        .parse()
+        .map_err(|_| format!("Invalid IP address in range: {}", range))?;
+    let bits: u32 = if address.is_ipv4() { 32 } else { 128 };
+    let prefix: u32 = match prefix {
+        Some(prefix) => prefix
+            .parse()
+            .ok()
+            .filter(|prefix| *prefix <= bits)
+            .ok_or_else(|| format!("Invalid prefix length in range: {}", range))?,
+        None => bits,
+    };
+
+    let size = 1u128.checked_shl(bits - prefix).unwrap_or(u128::MAX);
+    if size > MAX_RANGE_SIZE {
+        return Err(format!("Range {} is too large (at most {} addresses)", range, MAX_RANGE_SIZE).into());
+    }
+
+    let ips = match address {
+        IpAddr::V4(v4) => {
+            let network = u32::from(v4) & (u32::MAX.checked_shl(bits - prefix).unwrap_or(0));
+            (0..size as u32)
+                .map(|offset| IpAddr::V4(Ipv4Addr::from(network + offset)))
+                .collect()
+        }
+        IpAddr::V6(v6) => {
+            let network = u128::from(v6) & (u128::MAX.checked_shl(bits - prefix).unwrap_or(0));
+            (0..size)
+                .map(|offset| IpAddr::V6(Ipv6Addr::from(network + offset)))
+                .collect()
+        }
+    };
+    Ok(ips)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_addresses_and_cidrs() {
+        assert_eq!(expand_range("192.0.2.7/32").unwrap(), ["192.0.2.7".parse::<IpAddr>().unwrap()]);
+        assert_eq!(expand_range(" 192.0.2.7 ").unwrap(), ["192.0.2.7".parse::<IpAddr>().unwrap()]);
+
+        // Host bits are masked off, the range starts at the network address
+        let ips = expand_range("192.0.2.77/24").unwrap();
+        assert_eq!(ips.len(), 256);
+        assert_eq!(ips[0], "192.0.2.0".parse::<IpAddr>().unwrap());
+        assert_eq!(ips[255], "192.0.2.255".parse::<IpAddr>().unwrap());
+
+        let ips = expand_range("2001:db8::1234/120").unwrap();
+        assert_eq!(ips.len(), 256);
+        assert_eq!(ips[0], "2001:db8::1200".parse::<IpAddr>().unwrap());
+        assert_eq!(ips[255], "2001:db8::12ff".parse::<IpAddr>().unwrap());
+    }
+
+    #[test]
+    fn caps_the_range_size() {
+        assert_eq!(expand_range("10.1.0.0/16").unwrap().len(), MAX_RANGE_SIZE as usize);
+        assert_eq!(expand_range("2001:db8::/112").unwrap().len(), MAX_RANGE_SIZE as usize);
+
+        for range in ["10.0.0.0/15", "0.0.0.0/0", "2001:db8::/64"] {
+            let error = expand_range(range).unwrap_err().to_string();
+            assert!(error.contains("too large"), "{}: {}", range, error);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_ranges() {
+        let ranges = [
+            "",
+            "example.com",
+            "192.0.2.256",
+            "192.0.2.0/",
+            "192.0.2.0/33",
+            "192.0.2.0/-1",
+            "2001:db8::/129",
+        ];
+        for range in ranges {
+            assert!(expand_range(range).is_err(), "{}", range);
+        }
+    }
+}
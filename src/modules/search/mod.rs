@@ -1,229 +1,566 @@
 use crate::config::Config;
-use crate::modules::{Module, ModuleResult};
+use crate::findings::Finding;
+use crate::http::HttpClient;
+use crate::modules::{Category, Footprint, Module, ModuleResult};
+use crate::utils;
 use async_trait::async_trait;
-use reqwest::Client;
+use regex::Regex;
 use serde_json::json;
 use std::error::Error;
-use std::time::Duration;
-use tokio::time::sleep;
+use std::sync::LazyLock;
 
+// Result links of engine pages, compiled on first use
+static ABSOLUTE_LINK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"<a href="(https?://[^"]+)"[^>]*>"#).unwrap());
+static RELATIVE_LINK: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"<a href="(/[^"]+)"[^>]*>"#).unwrap());
+
+/// Scrapes the result links of `site:` searches on Google, Bing and GitHub.
 pub struct SearchModule {
-    client: Client,
+    client: HttpClient,
 }
 
 impl SearchModule {
-    pub fn new() -> Self {
-        let client = Client::builder()
-            .timeout(Duration::from_secs(10))
-            .build()
-            .expect("Failed to create HTTP client");
+    pub fn new(client: HttpClient) -> Self {
         Self { client }
     }
 
-    async fn search_google(&self, domain: &str, limit: u32) -> Result<Vec<String>, Box<dyn Error>> {
-        let mut results = Vec::new();
-        let mut start = 0;
-        
-        while results.len() < limit as usize {
-            let url = format!(
-                "https://www.google.com/search?q=site:{}&start={}",
-                urlencoding::encode(domain),
-                start
-            );
-
-            let response = self.client
-                .get(&url)
-                .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36")
-                .send()
-                .await?;
-
-            let text = response.text().await?;
-            
-            // Extract URLs from Google search results
-            if let Some(links) = extract_google_links(&text) {
-                results.extend(links);
-            }
+    /// Returns the result links of `query` along with the concatenated result pages.
+    async fn search_links(
+        &self,
+        engine: &dyn SearchEngine,
+        query: &str,
+        limit: u32,
+    ) -> Result<(Vec<String>, String), Box<dyn Error>> {
+        let pages = search_pages(&self.client, engine, query, limit).await?;
+        let links: Vec<String> = pages.iter().flat_map(|page| engine.extract_links(page)).collect();
+        Ok((links.into_iter().take(limit as usize).collect(), pages.concat()))
+    }
+}
 
-            start += 10;
-            if start >= 100 { // Google typically limits to 100 results
-                break;
-            }
+#[async_trait]
+impl Module for SearchModule {
+    fn name(&self) -> &'static str {
+        "search"
+    }
 
-            // Rate limiting
-            sleep(Duration::from_secs(2)).await;
-        }
+    fn description(&self) -> &'static str {
+        "Search engine information gathering module"
+    }
 
-        Ok(results.into_iter().take(limit as usize).collect())
-    }
-
-    async fn search_bing(&self, domain: &str, limit: u32) -> Result<Vec<String>, Box<dyn Error>> {
-        let mut results = Vec::new();
-        let mut offset = 0;
-        
-        while results.len() < limit as usize {
-            let url = format!(
-                "https://www.bing.com/search?q=site:{}&first={}",
-                urlencoding::encode(domain),
-                offset
-            );
-
-            let response = self.client
-                .get(&url)
-                .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36")
-                .send()
-                .await?;
-
-            let text = response.text().await?;
-            
-            // Extract URLs from Bing search results
-            if let Some(links) = extract_bing_links(&text) {
-                results.extend(links);
-            }
+    fn category(&self) -> Category {
+        Category::SearchEngine
+    }
+
+    fn footprint(&self) -> Footprint {
+        Footprint::Passive
+    }
 
-            offset += 10;
-            if offset >= 100 { // Bing typically limits to 100 results
-                break;
+    async fn run(&self, domain: &str, config: &Config) -> Result<ModuleResult, Box<dyn Error>> {
+        let mut metadata = json!({});
+        let mut all_results = Vec::new();
+        let mut emails = Vec::new();
+        let mut hostnames = Vec::new();
+
+        let site_query = format!("site:{}", domain);
+        let searches: [(&dyn SearchEngine, &str); 3] = [
+            (&Google, &site_query),
+            (&Bing, &site_query),
+            (&GitHub, domain),
+        ];
+
+        for (engine, query) in searches {
+            if let Ok((results, pages)) = self.search_links(engine, query, config.limit).await {
+                metadata[format!("{}_results", engine.name())] = json!(results);
+                all_results.extend(results);
+
+                let (found_emails, found_hostnames) = engine.extract(&pages, domain);
+                emails.extend(found_emails);
+                hostnames.extend(found_hostnames);
             }
+        }
 
-            // Rate limiting
-            sleep(Duration::from_secs(2)).await;
+        // Remove duplicates and sort
+        for values in [&mut all_results, &mut emails, &mut hostnames] {
+            values.sort();
+            values.dedup();
         }
 
-        Ok(results.into_iter().take(limit as usize).collect())
-    }
-
-    async fn search_github(&self, domain: &str, limit: u32) -> Result<Vec<String>, Box<dyn Error>> {
-        let mut results = Vec::new();
-        let mut page = 1;
-        
-        while results.len() < limit as usize {
-            let url = format!(
-                "https://github.com/search?q={}&type=code&p={}",
-                urlencoding::encode(domain),
-                page
-            );
-
-            let response = self.client
-                .get(&url)
-                .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36")
-                .send()
-                .await?;
-
-            let text = response.text().await?;
-            
-            // Extract URLs from GitHub search results
-            if let Some(links) = extract_github_links(&text) {
-                results.extend(links);
-            }
+        let mut findings: Vec<Finding> = all_results.iter().map(Finding::url).collect();
+        findings.extend(emails.iter().map(Finding::email));
+        findings.extend(hostnames.iter().map(Finding::hostname));
 
-            page += 1;
-            if page > 10 { // GitHub typically limits to 10 pages
-                break;
-            }
+        metadata["total_results"] = json!(all_results.len());
+        metadata["all_results"] = json!(all_results);
+        metadata["emails"] = json!(emails);
+        metadata["hostnames"] = json!(hostnames);
 
-            // Rate limiting
-            sleep(Duration::from_secs(2)).await;
+        Ok(ModuleResult {
+            source: "Search Engines".to_string(),
+            findings,
+            metadata: Some(metadata),
+        })
+    }
+}
+
+/// Fetches the result pages of `query` on `engine`, stepping the counter through the
+/// engine's span for `limit` and stopping early on its last page.
+pub async fn search_pages(
+    client: &HttpClient,
+    engine: &dyn SearchEngine,
+    query: &str,
+    limit: u32,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let (start, step) = engine.paging();
+    let end = start + engine.max_counter(limit);
+    let mut pages = Vec::new();
+    let mut counter = start;
+
+    while counter < end {
+        let request = client.get(engine.url(query, counter));
+        let response = client.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(format!("Unexpected status code: {}", response.status()).into());
         }
+        let page = response.text().await?;
+        let last_page = page.trim().is_empty() || engine.is_last_page(&page);
+        pages.push(page);
 
-        Ok(results.into_iter().take(limit as usize).collect())
+        counter += step;
+        if last_page || counter >= end {
+            break;
+        }
+
+        client.pause().await;
     }
+
+    Ok(pages)
 }
 
-fn extract_google_links(html: &str) -> Option<Vec<String>> {
-    let mut links = Vec::new();
-    let re = regex::Regex::new(r#"<a href="(https?://[^"]+)"[^>]*>"#).ok()?;
-    
-    for cap in re.captures_iter(html) {
-        if let Some(url) = cap.get(1) {
-            let url = url.as_str();
-            if !url.contains("google.com") {
-                links.push(url.to_string());
-            }
-        }
+/// Absolute links of a result page, skipping the engine's own pages.
+fn absolute_links(page: &str, own_host: &str) -> Vec<String> {
+    ABSOLUTE_LINK
+        .captures_iter(page)
+        .filter_map(|cap| cap.get(1))
+        .map(|url| url.as_str().replace("&amp;", "&"))
+        .filter(|url| !url.contains(own_host))
+        .collect()
+}
+
+/// A web search engine: how its queries and result page URLs are built, how
+/// its pages are stepped through and what is extracted from them.
+pub trait SearchEngine: Send + Sync {
+    /// Module name used with `-i`.
+    fn name(&self) -> &'static str;
+    /// Source shown in reports.
+    fn label(&self) -> &'static str;
+    fn description(&self) -> &'static str;
+
+    /// Query looking for mentions of `domain`.
+    fn query(&self, domain: &str) -> String {
+        format!("@{}", domain)
+    }
+
+    /// Result page URL of `query` for `counter`, the engine's result offset or page number.
+    fn url(&self, query: &str, counter: u32) -> String;
+
+    /// First counter value and increment between result pages.
+    fn paging(&self) -> (u32, u32);
+
+    /// How far past the first counter value to page for a result `limit`; the
+    /// pages fetched are those whose counter falls short of `start + max_counter`.
+    fn max_counter(&self, limit: u32) -> u32 {
+        limit
+    }
+
+    /// Whether `page` is the last one worth fetching, e.g. it has no next-page link.
+    fn is_last_page(&self, _page: &str) -> bool {
+        false
+    }
+
+    /// Result links found in `page`.
+    fn extract_links(&self, page: &str) -> Vec<String> {
+        absolute_links(page, &format!("{}.com", self.name()))
+    }
+
+    /// Emails and hostnames of `domain` found in the result pages, after decoding
+    /// and deobfuscation (see [`utils::prepare_text`]).
+    fn extract(&self, results: &str, domain: &str) -> (Vec<String>, Vec<String>) {
+        (utils::get_emails(results, domain), utils::get_hostnames(results, domain))
     }
-    
-    Some(links)
 }
 
-fn extract_bing_links(html: &str) -> Option<Vec<String>> {
-    let mut links = Vec::new();
-    let re = regex::Regex::new(r#"<a href="(https?://[^"]+)"[^>]*>"#).ok()?;
-    
-    for cap in re.captures_iter(html) {
-        if let Some(url) = cap.get(1) {
-            let url = url.as_str();
-            if !url.contains("bing.com") {
-                links.push(url.to_string());
-            }
-        }
+pub struct Google;
+
+impl SearchEngine for Google {
+    fn name(&self) -> &'static str {
+        "google"
+    }
+
+    fn label(&self) -> &'static str {
+        "Google"
+    }
+
+    fn description(&self) -> &'static str {
+        "Google email and hostname search"
+    }
+
+    fn query(&self, domain: &str) -> String {
+        format!("@\"{}\"", domain)
+    }
+
+    fn url(&self, query: &str, counter: u32) -> String {
+        format!(
+            "https://www.google.com/search?num=100&start={}&hl=en&meta=&q={}",
+            counter,
+            urlencoding::encode(query)
+        )
+    }
+
+    fn paging(&self) -> (u32, u32) {
+        (0, 100)
+    }
+
+    fn is_last_page(&self, page: &str) -> bool {
+        // No "Next" link, or the query was blocked by the unusual traffic page
+        !page.contains("id=\"pnnext\"") || page.contains("/sorry/")
     }
-    
-    Some(links)
 }
 
-fn extract_github_links(html: &str) -> Option<Vec<String>> {
-    let mut links = Vec::new();
-    let re = regex::Regex::new(r#"<a href="(/[^"]+)"[^>]*>"#).ok()?;
-    
-    for cap in re.captures_iter(html) {
-        if let Some(path) = cap.get(1) {
-            let path = path.as_str();
-            if path.starts_with("/") && !path.contains("github.com") {
-                links.push(format!("https://github.com{}", path));
-            }
+pub struct Bing;
+
+impl SearchEngine for Bing {
+    fn name(&self) -> &'static str {
+        "bing"
+    }
+
+    fn label(&self) -> &'static str {
+        "Bing"
+    }
+
+    fn description(&self) -> &'static str {
+        "Bing email and hostname search"
+    }
+
+    fn url(&self, query: &str, counter: u32) -> String {
+        format!(
+            "https://www.bing.com/search?q={}&count=50&first={}",
+            urlencoding::encode(query),
+            counter
+        )
+    }
+
+    fn paging(&self) -> (u32, u32) {
+        (0, 50)
+    }
+
+    fn is_last_page(&self, page: &str) -> bool {
+        !page.contains("sb_pagN")
+    }
+}
+
+pub struct Yahoo;
+
+impl SearchEngine for Yahoo {
+    fn name(&self) -> &'static str {
+        "yahoo"
+    }
+
+    fn label(&self) -> &'static str {
+        "Yahoo"
+    }
+
+    fn description(&self) -> &'static str {
+        "Yahoo email and hostname search"
+    }
+
+    fn url(&self, query: &str, counter: u32) -> String {
+        format!(
+            "https://search.yahoo.com/search?p={}&b={}&pz=10",
+            urlencoding::encode(query),
+            counter
+        )
+    }
+
+    fn paging(&self) -> (u32, u32) {
+        (1, 10)
+    }
+
+    fn is_last_page(&self, page: &str) -> bool {
+        !page.contains("class=\"next\"")
+    }
+}
+
+pub struct Ask;
+
+impl SearchEngine for Ask {
+    fn name(&self) -> &'static str {
+        "ask"
+    }
+
+    fn label(&self) -> &'static str {
+        "ASK"
+    }
+
+    fn description(&self) -> &'static str {
+        "ASK email and hostname search"
+    }
+
+    fn query(&self, domain: &str) -> String {
+        format!("@\"{}\"", domain)
+    }
+
+    fn url(&self, query: &str, counter: u32) -> String {
+        format!(
+            "https://www.ask.com/web?q={}&page={}",
+            urlencoding::encode(query),
+            counter
+        )
+    }
+
+    fn paging(&self) -> (u32, u32) {
+        (1, 1)
+    }
+
+    /// ASK paginates by page number, so the result limit is capped to a page count.
+    fn max_counter(&self, _limit: u32) -> u32 {
+        5
+    }
+
+    fn is_last_page(&self, page: &str) -> bool {
+        !page.contains("PartialWebPagination-next")
+    }
+}
+
+pub struct Dogpile;
+
+impl SearchEngine for Dogpile {
+    fn name(&self) -> &'static str {
+        "dogpile"
+    }
+
+    fn label(&self) -> &'static str {
+        "Dogpile"
+    }
+
+    fn description(&self) -> &'static str {
+        "Dogpile email and hostname search"
+    }
+
+    fn url(&self, query: &str, counter: u32) -> String {
+        format!(
+            "https://www.dogpile.com/search/web?qsi={}&q={}",
+            counter,
+            urlencoding::encode(query)
+        )
+    }
+
+    fn paging(&self) -> (u32, u32) {
+        (1, 15)
+    }
+
+    fn is_last_page(&self, page: &str) -> bool {
+        !page.contains("pagination__num--next")
+    }
+}
+
+pub struct Yandex;
+
+impl SearchEngine for Yandex {
+    fn name(&self) -> &'static str {
+        "yandex"
+    }
+
+    fn label(&self) -> &'static str {
+        "Yandex"
+    }
+
+    fn description(&self) -> &'static str {
+        "Yandex email and hostname search"
+    }
+
+    fn query(&self, domain: &str) -> String {
+        format!("\"@{}\"", domain)
+    }
+
+    /// Yandex pages by page number, 50 results per page.
+    fn url(&self, query: &str, counter: u32) -> String {
+        format!(
+            "https://yandex.com/search/?text={}&numdoc=50&p={}&lr=10418",
+            urlencoding::encode(query),
+            counter / 50
+        )
+    }
+
+    fn paging(&self) -> (u32, u32) {
+        (0, 50)
+    }
+
+    fn is_last_page(&self, page: &str) -> bool {
+        // No "next" pager link, or the query was answered with a captcha
+        !page.contains("pager__item_kind_next") || page.contains("showcaptcha")
+    }
+}
+
+pub struct GitHub;
+
+impl SearchEngine for GitHub {
+    fn name(&self) -> &'static str {
+        "github"
+    }
+
+    fn label(&self) -> &'static str {
+        "GitHub"
+    }
+
+    fn description(&self) -> &'static str {
+        "GitHub code search for emails and hostnames"
+    }
+
+    fn query(&self, domain: &str) -> String {
+        format!("\"{}\"", domain)
+    }
+
+    fn url(&self, query: &str, counter: u32) -> String {
+        format!(
+            "https://github.com/search?q={}&type=code&p={}",
+            urlencoding::encode(query),
+            counter
+        )
+    }
+
+    fn paging(&self) -> (u32, u32) {
+        (1, 1)
+    }
+
+    /// GitHub paginates by page number, so the result limit is capped to a page count.
+    fn max_counter(&self, _limit: u32) -> u32 {
+        10
+    }
+
+    fn is_last_page(&self, page: &str) -> bool {
+        !page.contains("rel=\"next\"")
+    }
+
+    /// GitHub result links are relative to github.com.
+    fn extract_links(&self, page: &str) -> Vec<String> {
+        RELATIVE_LINK
+            .captures_iter(page)
+            .filter_map(|cap| cap.get(1))
+            .map(|path| format!("https://github.com{}", path.as_str()))
+            .collect()
+    }
+}
+
+/// Exposes a [`SearchEngine`] as a module of its own.
+pub struct SearchEngineModule {
+    client: HttpClient,
+    engine: Box<dyn SearchEngine>,
+}
+
+impl SearchEngineModule {
+    pub fn new(client: HttpClient, engine: impl SearchEngine + 'static) -> Self {
+        Self {
+            client,
+            engine: Box::new(engine),
         }
     }
-    
-    Some(links)
 }
 
 #[async_trait]
-impl Module for SearchModule {
+impl Module for SearchEngineModule {
     fn name(&self) -> &'static str {
-        "search"
+        self.engine.name()
     }
 
     fn description(&self) -> &'static str {
-        "Search engine information gathering module"
+        self.engine.description()
+    }
+
+    fn category(&self) -> Category {
+        Category::SearchEngine
+    }
+
+    fn footprint(&self) -> Footprint {
+        Footprint::Passive
     }
 
     async fn run(&self, domain: &str, config: &Config) -> Result<ModuleResult, Box<dyn Error>> {
-        let mut data = Vec::new();
-        let mut metadata = json!({});
-        let mut all_results = Vec::new();
+        let query = self.engine.query(domain);
+        let pages = search_pages(&self.client, self.engine.as_ref(), &query, config.limit).await?;
+
+        let (emails, hostnames) = self.engine.extract(&pages.concat(), domain);
+
+        let mut findings: Vec<Finding> = emails.iter().map(Finding::email).collect();
+        findings.extend(hostnames.iter().map(Finding::hostname));
 
-        // Search Google
-        if let Ok(results) = self.search_google(domain, config.limit).await {
-            data.push(format!("Found {} results from Google", results.len()));
-            all_results.extend(results);
-            metadata["google_results"] = json!(results);
+        Ok(ModuleResult {
+            source: self.engine.label().to_string(),
+            findings,
+            metadata: Some(json!({
+                "emails": emails,
+                "hostnames": hostnames,
+            })),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::test_server::serve;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Pages of ten results at `base_url`, counting the URLs it is asked for.
+    struct Stub {
+        base_url: String,
+        requests: AtomicU32,
+    }
+
+    impl SearchEngine for Stub {
+        fn name(&self) -> &'static str {
+            "stub"
         }
 
-        // Search Bing
-        if let Ok(results) = self.search_bing(domain, config.limit).await {
-            data.push(format!("Found {} results from Bing", results.len()));
-            all_results.extend(results);
-            metadata["bing_results"] = json!(results);
+        fn label(&self) -> &'static str {
+            "Stub"
         }
 
-        // Search GitHub
-        if let Ok(results) = self.search_github(domain, config.limit).await {
-            data.push(format!("Found {} results from GitHub", results.len()));
-            all_results.extend(results);
-            metadata["github_results"] = json!(results);
+        fn description(&self) -> &'static str {
+            "Stub search engine"
         }
 
-        // Remove duplicates and sort
-        all_results.sort();
-        all_results.dedup();
+        fn url(&self, query: &str, counter: u32) -> String {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            format!("{}?q={}&start={}", self.base_url, query, counter)
+        }
 
-        metadata["total_results"] = json!(all_results.len());
-        metadata["all_results"] = json!(all_results);
+        fn paging(&self) -> (u32, u32) {
+            (0, 10)
+        }
+    }
 
-        Ok(ModuleResult {
-            source: "Search Engines".to_string(),
-            data,
-            metadata: Some(metadata),
-        })
+    #[tokio::test]
+    async fn fetches_limit_over_step_pages() {
+        let page = "HTTP/1.1 200 OK\r\nContent-Length: 4\r\nConnection: close\r\n\r\nmore";
+        let engine = Stub {
+            base_url: serve(&[page, page, page]).await,
+            requests: AtomicU32::new(0),
+        };
+        let client = HttpClient::from(reqwest::Client::new());
+
+        let pages = search_pages(&client, &engine, "example.com", 30).await.unwrap();
+        assert_eq!(pages.len(), 3);
+        assert_eq!(engine.requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn detects_the_last_page_of_every_paging_engine() {
+        let engines: [&dyn SearchEngine; 5] = [&Bing, &Yahoo, &Ask, &Dogpile, &Yandex];
+        for engine in engines {
+            assert!(engine.is_last_page("<html>no more results</html>"), "{}", engine.name());
+        }
+        assert!(!Ask.is_last_page(r#"<a class="PartialWebPagination-next" href="?page=2">Next</a>"#));
+        assert!(!Dogpile.is_last_page(r#"<a class="pagination__num pagination__num--next" href="?p=2">Next</a>"#));
+        assert!(!Yandex.is_last_page(r#"<a class="pager__item pager__item_kind_next" href="?p=1">next</a>"#));
     }
-} 
\ No newline at end of file
+}
@@ -1,61 +1,184 @@
 use crate::config::Config;
-use crate::modules::{Module, ModuleResult};
+use crate::findings::{Finding, HostDetails, ServiceBanner};
+use crate::http::{self, HttpClient, RateLimiter};
+use crate::modules::{Category, Credential, Footprint, Module, ModuleResult};
 use async_trait::async_trait;
-use reqwest::Client;
+use futures::future::join_all;
 use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
 use std::error::Error;
+use std::net::IpAddr;
+use std::sync::Arc;
+
+/// Facets counted for the search, with the number of values kept for each.
+const FACETS: &[&str] = &["org:10", "port:10", "country:10", "product:10"];
+
+/// Addresses per reverse DNS request.
+const REVERSE_DNS_BATCH: usize = 100;
+
+/// Parses the response of `/shodan/host/{ip}`, whose `data` holds one banner per service.
+fn host_details(info: &Value) -> HostDetails {
+    let banners = info
+        .get("data")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten();
+
+    let mut vulns: BTreeSet<String> = strings(info.get("vulns")).into_iter().collect();
+    let mut services = Vec::new();
+    for banner in banners {
+        // Banners list their vulnerabilities as an object keyed by CVE
+        if let Some(banner_vulns) = banner.get("vulns").and_then(|v| v.as_object()) {
+            vulns.extend(banner_vulns.keys().cloned());
+        }
+        let Some(port) = banner.get("port").and_then(|v| v.as_u64()) else {
+            continue;
+        };
+        let cpe = match strings(banner.get("cpe23")) {
+            cpe23 if cpe23.is_empty() => strings(banner.get("cpe")),
+            cpe23 => cpe23,
+        };
+        services.push(ServiceBanner {
+            port: port as u16,
+            transport: string(banner.get("transport")),
+            product: string(banner.get("product")),
+            version: string(banner.get("version")),
+            cpe,
+            http_title: string(banner.pointer("/http/title")),
+            tls_subject: banner
+                .pointer("/ssl/cert/subject")
+                .and_then(|v| v.as_object())
+                .map(|subject| {
+                    subject
+                        .iter()
+                        .map(|(key, value)| {
+                            format!("{}={}", key, value.as_str().unwrap_or_default())
+                        })
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                }),
+        });
+    }
+    services.sort_by_key(|service| service.port);
+
+    HostDetails {
+        hostnames: strings(info.get("hostnames")),
+        org: string(info.get("org")),
+        os: string(info.get("os")),
+        ports: info
+            .get("ports")
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|port| port.as_u64().map(|port| port as u16))
+            .collect(),
+        vulns: vulns.into_iter().collect(),
+        services,
+    }
+}
+
+fn string(value: Option<&Value>) -> Option<String> {
+    value
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(String::from)
+}
+
+fn strings(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str().map(String::from))
+        .collect()
+}
 
 pub struct ShodanModule {
-    client: Client,
+    client: HttpClient,
+    limiter: Arc<RateLimiter>,
 }
 
 impl ShodanModule {
-    pub fn new() -> Self {
-        Self {
-            client: Client::new(),
-        }
+    pub fn new(client: HttpClient, limiter: Arc<RateLimiter>) -> Self {
+        Self { client, limiter }
     }
 
-    async fn search_host(&self, api_key: &str, query: &str) -> Result<Value, Box<dyn Error>> {
+    /// One page of `query` results, 100 matches per page.
+    async fn search_host(
+        &self,
+        api_key: &str,
+        query: &str,
+        page: u32,
+    ) -> Result<Value, Box<dyn Error>> {
         let url = format!(
-            "https://api.shodan.io/shodan/host/search?key={}&query={}",
-            api_key, query
+            "https://api.shodan.io/shodan/host/search?key={}&query={}&page={}",
+            api_key,
+            urlencoding::encode(query),
+            page
         );
-        let response = self.client.get(&url).send().await?;
-        let data = response.json::<Value>().await?;
-        Ok(data)
+        let response = self
+            .limiter
+            .send(&self.client, self.client.get(&url))
+            .await?;
+        http::json(response).await
     }
 
-    async fn get_host_info(&self, api_key: &str, ip: &str) -> Result<Value, Box<dyn Error>> {
+    /// Result counts of `query` broken down by `FACETS`, without using query credits.
+    async fn count_host(&self, api_key: &str, query: &str) -> Result<Value, Box<dyn Error>> {
         let url = format!(
-            "https://api.shodan.io/shodan/host/{}?key={}",
-            ip, api_key
+            "https://api.shodan.io/shodan/host/count?key={}&query={}&facets={}",
+            api_key,
+            urlencoding::encode(query),
+            FACETS.join(",")
         );
-        let response = self.client.get(&url).send().await?;
-        let data = response.json::<Value>().await?;
-        Ok(data)
+        let response = self
+            .limiter
+            .send(&self.client, self.client.get(&url))
+            .await?;
+        http::json(response).await
+    }
+
+    async fn get_host_info(&self, api_key: &str, ip: &str) -> Result<Value, Box<dyn Error>> {
+        let url = format!("https://api.shodan.io/shodan/host/{}?key={}", ip, api_key);
+        let response = self
+            .limiter
+            .send(&self.client, self.client.get(&url))
+            .await?;
+        http::json(response).await
     }
 
-    async fn get_dns_resolve(&self, api_key: &str, domains: &[&str]) -> Result<Value, Box<dyn Error>> {
+    async fn get_dns_resolve(
+        &self,
+        api_key: &str,
+        domains: &[&str],
+    ) -> Result<Value, Box<dyn Error>> {
         let url = format!(
             "https://api.shodan.io/dns/resolve?key={}&hostnames={}",
             api_key,
             domains.join(",")
         );
-        let response = self.client.get(&url).send().await?;
-        let data = response.json::<Value>().await?;
-        Ok(data)
+        let response = self
+            .limiter
+            .send(&self.client, self.client.get(&url))
+            .await?;
+        http::json(response).await
     }
 
-    async fn get_dns_reverse(&self, api_key: &str, ips: &[&str]) -> Result<Value, Box<dyn Error>> {
+    async fn get_dns_reverse(
+        &self,
+        api_key: &str,
+        ips: &[String],
+    ) -> Result<Value, Box<dyn Error>> {
         let url = format!(
             "https://api.shodan.io/dns/reverse?key={}&ips={}",
             api_key,
             ips.join(",")
         );
-        let response = self.client.get(&url).send().await?;
-        let data = response.json::<Value>().await?;
-        Ok(data)
+        let response = self
+            .limiter
+            .send(&self.client, self.client.get(&url))
+            .await?;
+        http::json(response).await
     }
 }
 
@@ -66,54 +189,183 @@ impl Module for ShodanModule {
     }
 
     fn description(&self) -> &'static str {
-        "Shodan information gathering module"
+        "Shodan host search with banners, vulnerabilities, facets and reverse DNS"
+    }
+
+    fn category(&self) -> Category {
+        Category::HostSearch
+    }
+
+    fn footprint(&self) -> Footprint {
+        Footprint::Passive
+    }
+
+    fn credentials(&self) -> &'static [Credential] {
+        &[Credential::ShodanApiKey]
     }
 
     async fn run(&self, domain: &str, config: &Config) -> Result<ModuleResult, Box<dyn Error>> {
-        let api_key = config.shodan_api_key.as_ref().ok_or("Shodan API key not provided")?;
-        let mut data = Vec::new();
+        let api_key = config
+            .shodan_api_key
+            .as_ref()
+            .ok_or("Shodan API key not provided")?;
+        let mut findings = Vec::new();
         let mut metadata = serde_json::json!({});
 
-        // Search for the domain
-        let search_results = self.search_host(api_key, &format!("hostname:{}", domain)).await?;
-        if let Some(matches) = search_results.get("matches") {
-            if let Some(matches_array) = matches.as_array() {
-                for match_data in matches_array {
-                    if let Some(ip) = match_data.get("ip_str").and_then(|v| v.as_str()) {
-                        data.push(format!("IP: {}", ip));
-                        
-                        // Get detailed host information
-                        if let Ok(host_info) = self.get_host_info(api_key, ip).await {
-                            if let Some(hostnames) = host_info.get("hostnames").and_then(|v| v.as_array()) {
-                                for hostname in hostnames {
-                                    if let Some(name) = hostname.as_str() {
-                                        data.push(format!("Hostname: {}", name));
-                                    }
-                                }
-                            }
-
-                            if let Some(ports) = host_info.get("ports").and_then(|v| v.as_array()) {
-                                for port in ports {
-                                    if let Some(port_num) = port.as_u64() {
-                                        data.push(format!("Open Port: {}", port_num));
-                                    }
-                                }
-                            }
-
-                            if let Some(org) = host_info.get("org").and_then(|v| v.as_str()) {
-                                data.push(format!("Organization: {}", org));
-                            }
-
-                            if let Some(os) = host_info.get("os").and_then(|v| v.as_str()) {
-                                data.push(format!("Operating System: {}", os));
-                            }
-
-                            metadata["host_info"] = host_info;
-                        }
+        // Page through the search results up to the limit
+        let query = format!("hostname:{}", domain);
+        let mut matches = Vec::new();
+        let mut total = 0;
+        let mut page = 1;
+        while (matches.len() as u32) < config.limit {
+            let search_results = self.search_host(api_key, &query, page).await?;
+            total = search_results
+                .get("total")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0);
+            let page_matches = match search_results.get("matches").and_then(|v| v.as_array()) {
+                Some(page_matches) if !page_matches.is_empty() => page_matches.clone(),
+                _ => break,
+            };
+            matches.extend(page_matches);
+            if matches.len() as u64 >= total {
+                break;
+            }
+            page += 1;
+        }
+        matches.truncate(config.limit as usize);
+
+        let mut addresses: Vec<IpAddr> = Vec::new();
+        for match_data in &matches {
+            let address = match_data
+                .get("ip_str")
+                .and_then(|v| v.as_str())
+                .and_then(|ip| ip.parse().ok());
+            if let Some(address) = address {
+                if !addresses.contains(&address) {
+                    addresses.push(address);
+                }
+            }
+        }
+        metadata["total"] = total.into();
+        metadata["matches"] = matches.len().into();
+
+        // Summarize the results by organization, port, country and product
+        if let Ok(counts) = self.count_host(api_key, &query).await {
+            if let Some(facets) = counts.get("facets").and_then(|v| v.as_object()) {
+                for (facet, buckets) in facets {
+                    for bucket in buckets.as_array().into_iter().flatten() {
+                        let value = match bucket.get("value") {
+                            Some(Value::String(value)) => value.clone(),
+                            Some(value) => value.to_string(),
+                            None => continue,
+                        };
+                        findings.push(Finding::Facet {
+                            facet: facet.clone(),
+                            value,
+                            count: bucket.get("count").and_then(|v| v.as_u64()).unwrap_or(0),
+                        });
+                    }
+                }
+            }
+            metadata["facets"] = counts["facets"].clone();
+        }
+
+        // Get detailed host information, as many at a time as the client permits
+        let host_infos = join_all(addresses.iter().map(|&address| async move {
+            let host_info = self.get_host_info(api_key, &address.to_string()).await.ok();
+            (address, host_info)
+        }))
+        .await;
+
+        let mut resolutions = BTreeSet::new();
+        let mut hosts = BTreeMap::new();
+        for (address, host_info) in host_infos {
+            findings.push(Finding::IpAddress { address });
+
+            if let Some(host_info) = host_info {
+                let host = host_details(&host_info);
+
+                for hostname in &host.hostnames {
+                    resolutions.insert((hostname.clone(), address));
+                }
+
+                // One finding per banner, falling back to the bare port list
+                for service in &host.services {
+                    let name = match (&service.product, &service.version) {
+                        (Some(product), Some(version)) => Some(format!("{} {}", product, version)),
+                        (product, _) => product.clone(),
+                    };
+                    findings.push(Finding::OpenPort {
+                        ip: address,
+                        port: service.port,
+                        transport: service.transport.clone(),
+                        service: name,
+                    });
+                }
+                for port in &host.ports {
+                    if !host.services.iter().any(|service| service.port == *port) {
+                        findings.push(Finding::OpenPort {
+                            ip: address,
+                            port: *port,
+                            transport: None,
+                            service: None,
+                        });
                     }
                 }
+
+                for cve in &host.vulns {
+                    findings.push(Finding::Vulnerability {
+                        ip: address,
+                        id: cve.clone(),
+                    });
+                }
+
+                if let Some(org) = &host.org {
+                    findings.push(Finding::Organization { name: org.clone() });
+                }
+
+                if let Some(os) = &host.os {
+                    findings.push(Finding::OperatingSystem {
+                        ip: address,
+                        name: os.clone(),
+                    });
+                }
+
+                hosts.insert(address, host);
+            }
+        }
+        metadata["hosts"] = serde_json::to_value(&hosts)?;
+
+        // Reverse DNS of every address found
+        let ips: Vec<String> = addresses.iter().map(IpAddr::to_string).collect();
+        let mut reverse_dns = serde_json::Map::new();
+        for batch in ips.chunks(REVERSE_DNS_BATCH) {
+            let Ok(reverse) = self.get_dns_reverse(api_key, batch).await else {
+                continue;
+            };
+            for (ip, hostnames) in reverse.as_object().into_iter().flatten() {
+                let Ok(address) = ip.parse::<IpAddr>() else {
+                    continue;
+                };
+                for hostname in hostnames
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|v| v.as_str())
+                {
+                    resolutions.insert((hostname.to_string(), address));
+                }
+                reverse_dns.insert(ip.clone(), hostnames.clone());
             }
         }
+        metadata["dns_reverse"] = reverse_dns.into();
+
+        findings.extend(
+            resolutions
+                .into_iter()
+                .map(|(hostname, ip)| Finding::Resolution { hostname, ip }),
+        );
 
         // Get DNS resolution
         if let Ok(dns_data) = self.get_dns_resolve(api_key, &[domain]).await {
@@ -122,8 +374,47 @@ impl Module for ShodanModule {
 
         Ok(ModuleResult {
             source: "Shodan".to_string(),
-            data,
+            findings,
             metadata: Some(metadata),
         })
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn keeps_every_banner_and_vulnerability() {
+        let info = json!({
+            "ip_str": "192.0.2.10",
+            "hostnames": ["www.example.com"],
+            "org": "Example Hosting",
+            "ports": [22, 443],
+            "vulns": ["CVE-2021-23017"],
+            "data": [
+                {
+                    "port": 443, "transport": "tcp", "product": "nginx", "version": "1.18.0",
+                    "cpe23": ["cpe:2.3:a:f5:nginx:1.18.0"], "cpe": ["cpe:/a:f5:nginx:1.18.0"],
+                    "http": {"title": "Example"},
+                    "ssl": {"cert": {"subject": {"CN": "www.example.com"}}},
+                    "vulns": {"CVE-2019-20372": {"cvss": 4.3}}
+                },
+                {"port": 22, "transport": "tcp", "product": "OpenSSH"}
+            ]
+        });
+
+        let host = host_details(&info);
+        assert_eq!(host.vulns, ["CVE-2019-20372", "CVE-2021-23017"]);
+        assert_eq!(host.services.len(), 2);
+        assert_eq!(host.services[0].port, 22);
+        assert_eq!(host.services[0].version, None);
+
+        let https = &host.services[1];
+        assert_eq!(https.product.as_deref(), Some("nginx"));
+        assert_eq!(https.cpe, ["cpe:2.3:a:f5:nginx:1.18.0"]);
+        assert_eq!(https.http_title.as_deref(), Some("Example"));
+        assert_eq!(https.tls_subject.as_deref(), Some("CN=www.example.com"));
+    }
+}
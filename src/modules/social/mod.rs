@@ -0,0 +1,271 @@
+use crate::config::Config;
+use crate::findings::Finding;
+use crate::http::HttpClient;
+use crate::modules::search::{search_pages, Google};
+use crate::modules::{Category, Footprint, Module, ModuleResult};
+use crate::utils;
+use async_trait::async_trait;
+use reqwest::Url;
+use select::document::Document;
+use select::predicate::Name;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::error::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Network {
+    LinkedIn,
+    Twitter,
+    YouTube,
+    Reddit,
+    Instagram,
+}
+
+impl Network {
+    fn name(&self) -> &'static str {
+        match self {
+            Network::LinkedIn => "linkedin",
+            Network::Twitter => "twitter",
+            Network::YouTube => "youtube",
+            Network::Reddit => "reddit",
+            Network::Instagram => "instagram",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Network::LinkedIn => "LinkedIn",
+            Network::Twitter => "Twitter",
+            Network::YouTube => "Youtube",
+            Network::Reddit => "Reddit",
+            Network::Instagram => "Instagram",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Network::LinkedIn => "LinkedIn profiles and emails through Google site: queries",
+            Network::Twitter => "Twitter profiles and emails through Google site: queries",
+            Network::YouTube => "Youtube channels and emails through Google site: queries",
+            Network::Reddit => "Reddit users, subreddits and emails through Google site: queries",
+            Network::Instagram => "Instagram profiles and emails through Google site: queries",
+        }
+    }
+
+    fn site(&self) -> &'static str {
+        match self {
+            Network::LinkedIn => "linkedin.com",
+            Network::Twitter => "twitter.com",
+            Network::YouTube => "youtube.com",
+            Network::Reddit => "reddit.com",
+            Network::Instagram => "instagram.com",
+        }
+    }
+
+    /// Profile name and handle of a search result, or `None` when `url` is not a
+    /// profile page on this network. Titles look like:
+    ///
+    /// * LinkedIn: `Jane Doe - Security Engineer - Example | LinkedIn`
+    /// * Twitter: `Jane Doe (@jdoe) / X`
+    /// * Youtube: `Example Channel - YouTube`
+    /// * Reddit: `u/jdoe - Reddit` or `r/example - Reddit`
+    /// * Instagram: `Jane Doe (@jdoe) • Instagram photos and videos`
+    fn parse_result(&self, title: &str, url: &Url) -> Option<(Option<String>, Option<String>)> {
+        let host = url.host_str()?.trim_start_matches("www.").trim_start_matches("m.");
+        let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+        let first = *segments.first()?;
+        let second = segments.get(1).copied();
+
+        let (name, handle) = match self {
+            Network::LinkedIn => {
+                if !host.ends_with("linkedin.com") || !matches!(first, "in" | "company") {
+                    return None;
+                }
+                (title_before(title, &[" - ", " | "]), second.map(String::from))
+            }
+            Network::Twitter => {
+                if !matches!(host, "twitter.com" | "x.com")
+                    || matches!(first, "search" | "hashtag" | "i" | "intent" | "share" | "home")
+                {
+                    return None;
+                }
+                (title_before(title, &[" (@", " / ", " | "]), Some(first.to_string()))
+            }
+            Network::YouTube => {
+                if !host.ends_with("youtube.com") {
+                    return None;
+                }
+                let handle = match (first, second) {
+                    (handle, _) if handle.starts_with('@') => handle.trim_start_matches('@').to_string(),
+                    ("c" | "user" | "channel", Some(handle)) => handle.to_string(),
+                    _ => return None,
+                };
+                (title_before(title, &[" - YouTube"]), Some(handle))
+            }
+            Network::Reddit => {
+                if !host.ends_with("reddit.com") {
+                    return None;
+                }
+                let handle = match (first, second) {
+                    ("user" | "u", Some(user)) => format!("u/{}", user),
+                    ("r", Some(subreddit)) => format!("r/{}", subreddit),
+                    _ => return None,
+                };
+                (title_before(title, &[" - Reddit", " : "]), Some(handle))
+            }
+            Network::Instagram => {
+                if !host.ends_with("instagram.com")
+                    || matches!(first, "p" | "reel" | "reels" | "explore" | "stories" | "tv" | "accounts")
+                {
+                    return None;
+                }
+                (title_before(title, &[" (@", " • "]), Some(first.to_string()))
+            }
+        };
+
+        Some((name, handle))
+    }
+}
+
+/// The part of `title` before the first of `separators`, if any text is left.
+fn title_before(title: &str, separators: &[&str]) -> Option<String> {
+    let end = separators
+        .iter()
+        .filter_map(|separator| title.find(separator))
+        .min()
+        .unwrap_or(title.len());
+    let name = title[..end].trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// (URL, title) of every result on a Google result page.
+fn search_results(page: &str) -> Vec<(String, String)> {
+    let document = Document::from(page);
+    document
+        .find(Name("a"))
+        .filter_map(|link| {
+            let href = link.attr("href")?;
+            let title = link.find(Name("h3")).next()?.text();
+            // Without JavaScript Google links results through /url?q=<target>&...
+            let url = match href.strip_prefix("/url?q=") {
+                Some(target) => urlencoding::decode(target.split('&').next()?).ok()?.into_owned(),
+                None => href.to_string(),
+            };
+            Some((url, title.trim().to_string()))
+        })
+        .collect()
+}
+
+pub struct SocialModule {
+    client: HttpClient,
+    network: Network,
+}
+
+impl SocialModule {
+    pub fn new(client: HttpClient, network: Network) -> Self {
+        Self { client, network }
+    }
+}
+
+#[async_trait]
+impl Module for SocialModule {
+    fn name(&self) -> &'static str {
+        self.network.name()
+    }
+
+    fn description(&self) -> &'static str {
+        self.network.description()
+    }
+
+    fn category(&self) -> Category {
+        Category::SocialNetwork
+    }
+
+    fn footprint(&self) -> Footprint {
+        Footprint::Passive
+    }
+
+    async fn run(&self, domain: &str, config: &Config) -> Result<ModuleResult, Box<dyn Error>> {
+        let query = format!("site:{} @\"{}\"", self.network.site(), domain);
+        let results = search_pages(&self.client, &Google, &query, config.limit)
+            .await?
+            .concat();
+
+        let emails = utils::get_emails(&results, domain);
+        let hostnames = utils::get_hostnames(&results, domain);
+
+        // One profile per URL, without tracking parameters
+        let mut profiles = BTreeMap::new();
+        for (url, title) in search_results(&results) {
+            let Ok(mut url) = Url::parse(&url) else {
+                continue;
+            };
+            url.set_query(None);
+            url.set_fragment(None);
+            if let Some((name, handle)) = self.network.parse_result(&title, &url) {
+                profiles.entry(url.to_string()).or_insert((name, handle));
+            }
+        }
+
+        let mut findings: Vec<Finding> = emails.iter().map(Finding::email).collect();
+        findings.extend(hostnames.iter().map(Finding::hostname));
+        findings.extend(profiles.iter().map(|(url, (name, handle))| Finding::SocialProfile {
+            network: self.network.label().to_string(),
+            name: name.clone(),
+            handle: handle.clone(),
+            url: url.clone(),
+        }));
+
+        Ok(ModuleResult {
+            source: self.network.label().to_string(),
+            findings,
+            metadata: Some(json!({
+                "site": self.network.site(),
+                "emails": emails,
+                "hostnames": hostnames,
+                "profiles": profiles.len(),
+            })),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_profile_results() {
+        let page = r#"
+            <a href="/url?q=https://www.linkedin.com/in/jdoe%3Ftrk%3Dx&amp;sa=U"><h3>Jane Doe - Security Engineer - Example | LinkedIn</h3></a>
+            <a href="https://twitter.com/jdoe"><h3>Jane Doe (@jdoe) / X</h3></a>
+            <a href="https://www.reddit.com/r/example/"><h3>r/example - Reddit</h3></a>
+            <a href="https://www.instagram.com/p/abc123/"><h3>Post by Jane</h3></a>
+            <a href="https://www.youtube.com/@examplechannel"><h3>Example Channel - YouTube</h3></a>
+            <a href="https://www.youtube.com/watch?v=dQw4w9WgXcQ"><h3>Product launch - YouTube</h3></a>
+        "#;
+        let results = search_results(page);
+        assert_eq!(results.len(), 6);
+
+        let parse = |network: Network, (url, title): &(String, String)| {
+            network.parse_result(title, &Url::parse(url).unwrap())
+        };
+        assert_eq!(
+            parse(Network::LinkedIn, &results[0]),
+            Some((Some("Jane Doe".to_string()), Some("jdoe".to_string())))
+        );
+        assert_eq!(
+            parse(Network::Twitter, &results[1]),
+            Some((Some("Jane Doe".to_string()), Some("jdoe".to_string())))
+        );
+        assert_eq!(
+            parse(Network::Reddit, &results[2]),
+            Some((Some("r/example".to_string()), Some("r/example".to_string())))
+        );
+        assert_eq!(parse(Network::Instagram, &results[3]), None);
+        assert_eq!(
+            parse(Network::YouTube, &results[4]),
+            Some((Some("Example Channel".to_string()), Some("examplechannel".to_string())))
+        );
+        assert_eq!(parse(Network::YouTube, &results[5]), None);
+    }
+}
@@ -1,19 +1,20 @@
 use crate::config::Config;
-use crate::modules::{Module, ModuleResult};
+use crate::findings::Finding;
+use crate::http::{self, HttpClient, RateLimiter};
+use crate::modules::{Category, Credential, Footprint, Module, ModuleResult};
 use async_trait::async_trait;
-use reqwest::Client;
 use serde_json::json;
 use std::error::Error;
+use std::sync::Arc;
 
 pub struct SpyseModule {
-    client: Client,
+    client: HttpClient,
+    limiter: Arc<RateLimiter>,
 }
 
 impl SpyseModule {
-    pub fn new() -> Self {
-        Self {
-            client: Client::new(),
-        }
+    pub fn new(client: HttpClient, limiter: Arc<RateLimiter>) -> Self {
+        Self { client, limiter }
     }
 
     async fn search_domain(
@@ -28,14 +29,12 @@ impl SpyseModule {
             limit
         );
 
-        let response = self.client
+        let request = self.client
             .get(&url)
-            .header("Authorization", format!("Bearer {}", api_key))
-            .send()
-            .await?;
+            .header("Authorization", format!("Bearer {}", api_key));
+        let response = self.limiter.send(&self.client, request).await?;
 
-        let data = response.json::<serde_json::Value>().await?;
-        Ok(data)
+        http::json(response).await
     }
 
     async fn get_domain_details(
@@ -48,14 +47,12 @@ impl SpyseModule {
             urlencoding::encode(domain)
         );
 
-        let response = self.client
+        let request = self.client
             .get(&url)
-            .header("Authorization", format!("Bearer {}", api_key))
-            .send()
-            .await?;
+            .header("Authorization", format!("Bearer {}", api_key));
+        let response = self.limiter.send(&self.client, request).await?;
 
-        let data = response.json::<serde_json::Value>().await?;
-        Ok(data)
+        http::json(response).await
     }
 
     async fn get_dns_records(
@@ -68,14 +65,12 @@ impl SpyseModule {
             urlencoding::encode(domain)
         );
 
-        let response = self.client
+        let request = self.client
             .get(&url)
-            .header("Authorization", format!("Bearer {}", api_key))
-            .send()
-            .await?;
+            .header("Authorization", format!("Bearer {}", api_key));
+        let response = self.limiter.send(&self.client, request).await?;
 
-        let data = response.json::<serde_json::Value>().await?;
-        Ok(data)
+        http::json(response).await
     }
 }
 
@@ -89,9 +84,21 @@ impl Module for SpyseModule {
         "Spyse information gathering module"
     }
 
+    fn category(&self) -> Category {
+        Category::HostSearch
+    }
+
+    fn footprint(&self) -> Footprint {
+        Footprint::Passive
+    }
+
+    fn credentials(&self) -> &'static [Credential] {
+        &[Credential::SpyseApiKey]
+    }
+
     async fn run(&self, domain: &str, config: &Config) -> Result<ModuleResult, Box<dyn Error>> {
         let api_key = config.spyse_api_key.as_ref().ok_or("Spyse API key not provided")?;
-        let mut data = Vec::new();
+        let mut findings = Vec::new();
         let mut metadata = json!({});
 
         // Search for domain
@@ -99,7 +106,7 @@ impl Module for SpyseModule {
         if let Some(results) = search_results.get("data").and_then(|v| v.as_array()) {
             for result in results {
                 if let Some(name) = result.get("name").and_then(|v| v.as_str()) {
-                    data.push(format!("Domain: {}", name));
+                    findings.push(Finding::hostname(name));
                 }
             }
         }
@@ -107,13 +114,22 @@ impl Module for SpyseModule {
         // Get domain details
         if let Ok(details) = self.get_domain_details(api_key, domain).await {
             if let Some(created_date) = details.get("created_date").and_then(|v| v.as_str()) {
-                data.push(format!("Created Date: {}", created_date));
+                findings.push(Finding::WhoisField {
+                    key: "Created Date".to_string(),
+                    value: created_date.to_string(),
+                });
             }
             if let Some(expiry_date) = details.get("expiry_date").and_then(|v| v.as_str()) {
-                data.push(format!("Expiry Date: {}", expiry_date));
+                findings.push(Finding::WhoisField {
+                    key: "Expiry Date".to_string(),
+                    value: expiry_date.to_string(),
+                });
             }
             if let Some(registrar) = details.get("registrar").and_then(|v| v.as_str()) {
-                data.push(format!("Registrar: {}", registrar));
+                findings.push(Finding::WhoisField {
+                    key: "Registrar".to_string(),
+                    value: registrar.to_string(),
+                });
             }
             metadata["domain_details"] = details;
         }
@@ -124,7 +140,12 @@ impl Module for SpyseModule {
                 for record in records {
                     if let Some(record_type) = record.get("type").and_then(|v| v.as_str()) {
                         if let Some(value) = record.get("value").and_then(|v| v.as_str()) {
-                            data.push(format!("{} Record: {}", record_type, value));
+                            findings.push(Finding::DnsRecord {
+                                name: domain.to_string(),
+                                record_type: record_type.to_string(),
+                                value: value.to_string(),
+                                ttl: record.get("ttl").and_then(|v| v.as_u64()).map(|ttl| ttl as u32),
+                            });
                         }
                     }
                 }
@@ -134,7 +155,7 @@ impl Module for SpyseModule {
 
         Ok(ModuleResult {
             source: "Spyse".to_string(),
-            data,
+            findings,
             metadata: Some(metadata),
         })
     }
@@ -1,31 +1,35 @@
 use crate::config::Config;
-use crate::modules::{Module, ModuleResult};
+use crate::findings::Finding;
+use crate::http::HttpClient;
+use crate::modules::{Category, Footprint, Module, ModuleResult};
+use crate::utils;
 use async_trait::async_trait;
-use reqwest::Client;
+use futures::future::join_all;
 use serde_json::json;
 use std::error::Error;
 use std::net::IpAddr;
 use std::str::FromStr;
-use tokio::time::{sleep, Duration};
+use tokio::sync::Mutex;
+use tokio::time::{interval, Duration, MissedTickBehavior};
+
+/// Gap between two vhost requests, across all addresses.
+const CHECK_INTERVAL: Duration = Duration::from_millis(100);
 
 pub struct VhostsModule {
-    client: Client,
+    client: HttpClient,
 }
 
 impl VhostsModule {
-    pub fn new() -> Self {
-        Self {
-            client: Client::new(),
-        }
+    pub fn new(client: HttpClient) -> Self {
+        Self { client }
     }
 
     async fn check_vhost(&self, ip: &str, hostname: &str) -> Result<bool, Box<dyn Error>> {
         let url = format!("http://{}", ip);
-        let response = self.client
+        let request = self.client
             .get(&url)
-            .header("Host", hostname)
-            .send()
-            .await?;
+            .header("Host", hostname);
+        let response = self.client.send(request).await?;
 
         Ok(response.status().is_success())
     }
@@ -43,27 +47,6 @@ impl VhostsModule {
             })
             .collect())
     }
-
-    fn get_common_subdomains(&self) -> Vec<String> {
-        vec![
-            "www", "mail", "remote", "blog", "webmail", "server",
-            "ns1", "ns2", "smtp", "secure", "vpn", "m", "shop",
-            "ftp", "mail2", "test", "portal", "ns", "ww1", "host",
-            "support", "dev", "web", "bbs", "ww42", "mx", "email",
-            "cloud", "1", "mail1", "2", "forum", "owa", "www2",
-            "gw", "admin", "store", "mx1", "cdn", "api", "exchange",
-            "app", "gov", "2tty", "vps", "govyty", "hgfgdf", "we",
-            "media", "ssl", "secure", "vpn", "m", "shop", "ftp",
-            "mail2", "test", "portal", "ns", "ww1", "host", "support",
-            "dev", "web", "bbs", "ww42", "mx", "email", "cloud",
-            "1", "mail1", "2", "forum", "owa", "www2", "gw", "admin",
-            "store", "mx1", "cdn", "api", "exchange", "app", "gov",
-            "2tty", "vps", "govyty", "hgfgdf", "we", "media", "ssl",
-        ]
-        .into_iter()
-        .map(String::from)
-        .collect()
-    }
 }
 
 #[async_trait]
@@ -76,8 +59,16 @@ impl Module for VhostsModule {
         "Virtual host detection module"
     }
 
-    async fn run(&self, domain: &str, config: &Config) -> Result<ModuleResult, Box<dyn Error>> {
-        let mut data = Vec::new();
+    fn category(&self) -> Category {
+        Category::VirtualHosts
+    }
+
+    fn footprint(&self) -> Footprint {
+        Footprint::Active
+    }
+
+    async fn run(&self, domain: &str, _config: &Config) -> Result<ModuleResult, Box<dyn Error>> {
+        let mut findings = Vec::new();
         let mut metadata = json!({});
         let mut found_vhosts = Vec::new();
 
@@ -86,26 +77,46 @@ impl Module for VhostsModule {
         if ips.is_empty() {
             return Ok(ModuleResult {
                 source: "VHosts".to_string(),
-                data: vec!["No IP addresses found for domain".to_string()],
+                findings: Vec::new(),
                 metadata: None,
             });
         }
 
-        // Check each IP address
-        for ip in ips {
-            data.push(format!("Checking IP: {}", ip));
-            
-            // Check common subdomains
-            for subdomain in self.get_common_subdomains() {
-                let hostname = format!("{}.{}", subdomain, domain);
-                
-                if self.check_vhost(&ip, &hostname).await? {
-                    data.push(format!("Found virtual host: {}", hostname));
+        // Check common subdomains against each IP address
+        let mut candidates = Vec::new();
+        for ip in &ips {
+            let address: IpAddr = ip.parse()?;
+            for subdomain in utils::COMMON_SUBDOMAINS {
+                candidates.push((address, format!("{}.{}", subdomain, domain)));
+            }
+        }
+
+        // Requests start at most once per CHECK_INTERVAL
+        let mut ticker = interval(CHECK_INTERVAL);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let limiter = &Mutex::new(ticker);
+
+        let checks = join_all(candidates.into_iter().map(|(address, hostname)| async move {
+            limiter.lock().await.tick().await;
+            let found = self
+                .check_vhost(&address.to_string(), &hostname)
+                .await
+                .map_err(|e| e.to_string());
+            (address, hostname, found)
+        }))
+        .await;
+
+        for (address, hostname, found) in checks {
+            match found {
+                Ok(true) => {
+                    findings.push(Finding::VirtualHost {
+                        ip: address,
+                        hostname: hostname.clone(),
+                    });
                     found_vhosts.push(hostname);
                 }
-                
-                // Rate limiting
-                sleep(Duration::from_millis(100)).await;
+                Ok(false) => {}
+                Err(e) => log::warn!("Checking {} on {} failed: {}", hostname, address, e),
             }
         }
 
@@ -114,7 +125,7 @@ impl Module for VhostsModule {
 
         Ok(ModuleResult {
             source: "VHosts".to_string(),
-            data,
+            findings,
             metadata: Some(metadata),
         })
     }
@@ -0,0 +1,183 @@
+use crate::config::Config;
+use crate::findings::Finding;
+use crate::http::{self, HttpClient, RateLimiter};
+use crate::modules::{Category, Credential, Footprint, Module, ModuleResult};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::error::Error;
+use std::sync::Arc;
+
+const VIRUSTOTAL_API_URL: &str = "https://www.virustotal.com/api/v3";
+
+/// Largest page size the subdomains endpoint accepts.
+const PAGE_SIZE: u32 = 40;
+
+pub struct VirusTotalModule {
+    client: HttpClient,
+    limiter: Arc<RateLimiter>,
+    base_url: String,
+}
+
+impl VirusTotalModule {
+    pub fn new(client: HttpClient, limiter: Arc<RateLimiter>) -> Self {
+        Self::with_base_url(client, limiter, VIRUSTOTAL_API_URL)
+    }
+
+    /// Queries a VirusTotal v3 compatible API other than virustotal.com.
+    pub fn with_base_url(client: HttpClient, limiter: Arc<RateLimiter>, base_url: &str) -> Self {
+        Self {
+            client,
+            limiter,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn get_subdomains(
+        &self,
+        api_key: &str,
+        domain: &str,
+        cursor: Option<&str>,
+    ) -> Result<Value, Box<dyn Error>> {
+        let mut url = format!(
+            "{}/domains/{}/subdomains?limit={}",
+            self.base_url,
+            urlencoding::encode(domain),
+            PAGE_SIZE
+        );
+        if let Some(cursor) = cursor {
+            url.push_str(&format!("&cursor={}", urlencoding::encode(cursor)));
+        }
+
+        let request = self.client.get(&url).header("x-apikey", api_key);
+        let response = self.limiter.send(&self.client, request).await?;
+
+        http::json(response).await
+    }
+}
+
+#[async_trait]
+impl Module for VirusTotalModule {
+    fn name(&self) -> &'static str {
+        "virustotal"
+    }
+
+    fn description(&self) -> &'static str {
+        "VirusTotal subdomain search module"
+    }
+
+    fn category(&self) -> Category {
+        Category::SubdomainSearch
+    }
+
+    fn footprint(&self) -> Footprint {
+        Footprint::Passive
+    }
+
+    fn credentials(&self) -> &'static [Credential] {
+        &[Credential::VirusTotalApiKey]
+    }
+
+    async fn run(&self, domain: &str, config: &Config) -> Result<ModuleResult, Box<dyn Error>> {
+        let api_key = config.virustotal_api_key.as_ref().ok_or("VirusTotal API key not provided")?;
+        let mut findings = Vec::new();
+        let mut hostnames = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        // Follow the cursor until the results run out or reach the limit
+        while hostnames.len() < config.limit as usize {
+            let page = self.get_subdomains(api_key, domain, cursor.as_deref()).await?;
+            let entries = page.get("data").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+            if entries.is_empty() {
+                break;
+            }
+
+            let remaining = config.limit as usize - hostnames.len();
+            for entry in entries.iter().take(remaining) {
+                let Some(hostname) = entry.get("id").and_then(|v| v.as_str()) else {
+                    continue;
+                };
+                findings.push(Finding::hostname(hostname));
+                hostnames.push(hostname.to_string());
+
+                let records = entry
+                    .pointer("/attributes/last_dns_records")
+                    .and_then(|v| v.as_array())
+                    .cloned()
+                    .unwrap_or_default();
+                for record in records {
+                    if let (Some(record_type), Some(value)) = (
+                        record.get("type").and_then(|v| v.as_str()),
+                        record.get("value").and_then(|v| v.as_str()),
+                    ) {
+                        findings.push(Finding::DnsRecord {
+                            name: hostname.to_string(),
+                            record_type: record_type.to_string(),
+                            value: value.trim_end_matches('.').to_string(),
+                            ttl: record.get("ttl").and_then(|v| v.as_u64()).map(|ttl| ttl as u32),
+                        });
+                    }
+                }
+            }
+
+            cursor = page
+                .pointer("/meta/cursor")
+                .and_then(|v| v.as_str())
+                .filter(|cursor| !cursor.is_empty())
+                .map(String::from);
+            if cursor.is_none() {
+                break;
+            }
+        }
+
+        Ok(ModuleResult {
+            source: "VirusTotal".to_string(),
+            findings,
+            metadata: Some(json!({ "hostnames": hostnames })),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::test_server::{json_response, serve};
+    use clap::Parser;
+
+    #[tokio::test]
+    async fn follows_the_cursor_up_to_the_limit() {
+        let first = json_response(
+            r#"{"data": [
+                {"id": "www.example.com", "attributes": {"last_dns_records": [
+                    {"type": "A", "value": "192.0.2.80", "ttl": 300},
+                    {"type": "CNAME", "value": "cdn.example.net.", "ttl": 60}
+                ]}},
+                {"id": "mail.example.com", "attributes": {}}
+            ], "meta": {"cursor": "page-2"}}"#,
+        );
+        let second = json_response(
+            r#"{"data": [{"id": "vpn.example.com"}, {"id": "dev.example.com"}], "meta": {}}"#,
+        );
+        let base_url = serve(&[&first, &second]).await;
+
+        let mut config = Config::new(&crate::Args::parse_from(["gasmask"])).unwrap();
+        config.virustotal_api_key = Some("key".to_string());
+        config.limit = 3;
+        let module = VirusTotalModule::with_base_url(
+            reqwest::Client::new().into(),
+            Arc::new(RateLimiter::new(100.0, 10)),
+            &base_url,
+        );
+        let result = module.run("example.com", &config).await.unwrap();
+
+        assert_eq!(
+            result.metadata.unwrap()["hostnames"],
+            json!(["www.example.com", "mail.example.com", "vpn.example.com"])
+        );
+        assert!(result.findings.contains(&Finding::DnsRecord {
+            name: "www.example.com".to_string(),
+            record_type: "CNAME".to_string(),
+            value: "cdn.example.net".to_string(),
+            ttl: Some(60),
+        }));
+    }
+}
@@ -1,5 +1,6 @@
 use crate::config::Config;
-use crate::modules::{Module, ModuleResult};
+use crate::findings::Finding;
+use crate::modules::{Category, Footprint, Module, ModuleResult};
 use async_trait::async_trait;
 use std::error::Error;
 use whois::WhoisLookup;
@@ -26,31 +27,40 @@ impl Module for WhoisModule {
         "WHOIS information gathering module"
     }
 
+    fn category(&self) -> Category {
+        Category::Whois
+    }
+
+    fn footprint(&self) -> Footprint {
+        Footprint::Passive
+    }
+
     async fn run(&self, domain: &str, _config: &Config) -> Result<ModuleResult, Box<dyn Error>> {
         let whois_data = self.client.lookup(domain)?;
         
-        let mut data = Vec::new();
+        let mut findings = Vec::new();
         let mut metadata = serde_json::json!({});
 
         // Parse WHOIS data into structured format
         for line in whois_data.lines() {
-            if !line.trim().is_empty() {
-                data.push(line.trim().to_string());
-                
-                // Try to parse key-value pairs
-                if let Some((key, value)) = line.split_once(':') {
+            // Try to parse key-value pairs
+            if let Some((key, value)) = line.split_once(':') {
+                let value = value.trim();
+                if !value.is_empty() {
+                    findings.push(Finding::WhoisField {
+                        key: key.trim().to_string(),
+                        value: value.to_string(),
+                    });
                     let key = key.trim().to_lowercase().replace(' ', "_");
-                    let value = value.trim();
-                    if !value.is_empty() {
-                        metadata[key] = serde_json::json!(value);
-                    }
+                    metadata[key] = serde_json::json!(value);
                 }
             }
         }
+        metadata["raw"] = serde_json::json!(whois_data);
 
         Ok(ModuleResult {
             source: "WHOIS".to_string(),
-            data,
+            findings,
             metadata: Some(metadata),
         })
     }
@@ -0,0 +1,776 @@
+use crate::correlation::{Inventory, Sources};
+use crate::findings::{Finding, HostDetails, ServiceBanner};
+use crate::modules::ModuleResult;
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{self, Write};
+use std::net::IpAddr;
+
+/// Everything a reporter needs to render a scan.
+pub struct Report<'a> {
+    pub domain: &'a str,
+    pub results: &'a [ModuleResult],
+    pub inventory: &'a Inventory,
+}
+
+pub trait Reporter {
+    /// File extension (and `--format` name) of the output.
+    fn extension(&self) -> &'static str;
+    fn render(&self, report: &Report) -> Result<String, Box<dyn Error>>;
+}
+
+pub fn get_available_formats() -> Vec<&'static str> {
+    vec!["txt", "md", "xml", "html", "json"]
+}
+
+pub fn get_reporter_by_name(name: &str) -> Option<Box<dyn Reporter>> {
+    match name {
+        "txt" => Some(Box::new(TextReporter)),
+        "md" => Some(Box::new(MarkdownReporter)),
+        "xml" => Some(Box::new(XmlReporter)),
+        "html" => Some(Box::new(HtmlReporter)),
+        "json" => Some(Box::new(JsonReporter)),
+        _ => None,
+    }
+}
+
+pub struct TextReporter;
+
+impl Reporter for TextReporter {
+    fn extension(&self) -> &'static str {
+        "txt"
+    }
+
+    fn render(&self, report: &Report) -> Result<String, Box<dyn Error>> {
+        let mut out = String::new();
+        writeln!(out, "[+] GasMasK report for {}", report.domain)?;
+        writeln!(out)?;
+
+        for result in report.results {
+            writeln!(out, "[+] {} results", result.source)?;
+            writeln!(out, "-------------------------")?;
+            for finding in listed(result) {
+                writeln!(out, "{}", finding)?;
+            }
+            let facets = facets(result);
+            if !facets.is_empty() {
+                writeln!(out)?;
+                writeln!(out, "{:<10} {:<40} Count", "Facet", "Value")?;
+                for (facet, value, count) in facets {
+                    writeln!(out, "{:<10} {:<40} {}", facet, value, count)?;
+                }
+            }
+            writeln!(out)?;
+        }
+
+        for (source, hosts) in host_reports(report.results) {
+            writeln!(out, "[+] {} Report", source)?;
+            writeln!(out, "-------------------------")?;
+            write_host_report(&mut out, &hosts)?;
+        }
+
+        writeln!(out, "[+] Final Report")?;
+        writeln!(out, "-------------------------")?;
+        write_final_report(&mut out, report.inventory)?;
+
+        Ok(out)
+    }
+}
+
+pub struct MarkdownReporter;
+
+impl Reporter for MarkdownReporter {
+    fn extension(&self) -> &'static str {
+        "md"
+    }
+
+    fn render(&self, report: &Report) -> Result<String, Box<dyn Error>> {
+        let mut out = String::new();
+        writeln!(
+            out,
+            "# GasMasK report for {}",
+            escape_markdown(report.domain)
+        )?;
+        writeln!(out)?;
+
+        for result in report.results {
+            writeln!(out, "---")?;
+            writeln!(out)?;
+            writeln!(out, "## {} results", escape_markdown(&result.source))?;
+            writeln!(out)?;
+            for finding in listed(result) {
+                writeln!(out, "* {}", escape_markdown(&finding.to_string()))?;
+            }
+            let facets = facets(result);
+            if !facets.is_empty() {
+                writeln!(out)?;
+                writeln!(out, "| Facet | Value | Count |")?;
+                writeln!(out, "|-------|-------|------:|")?;
+                for (facet, value, count) in facets {
+                    writeln!(
+                        out,
+                        "| {} | {} | {} |",
+                        escape_markdown(facet),
+                        escape_markdown(value),
+                        count
+                    )?;
+                }
+            }
+            writeln!(out)?;
+        }
+
+        for (source, hosts) in host_reports(report.results) {
+            writeln!(out, "---")?;
+            writeln!(out)?;
+            writeln!(out, "## {} Report", escape_markdown(source))?;
+            writeln!(out)?;
+            for (ip, host) in &hosts {
+                writeln!(out, "### {}", ip)?;
+                writeln!(out)?;
+                writeln!(
+                    out,
+                    "* Hostnames: {}",
+                    escape_markdown(&host.hostnames.join(", "))
+                )?;
+                writeln!(
+                    out,
+                    "* Organization: {}",
+                    escape_markdown(host.org.as_deref().unwrap_or("n/a"))
+                )?;
+                writeln!(
+                    out,
+                    "* Operating System: {}",
+                    escape_markdown(host.os.as_deref().unwrap_or("n/a"))
+                )?;
+                writeln!(
+                    out,
+                    "* Vulnerabilities: {}",
+                    escape_markdown(&host.vulns.join(", "))
+                )?;
+                writeln!(out)?;
+                if !host.services.is_empty() {
+                    writeln!(out, "| Port | Product | CPE | HTTP Title | TLS Subject |")?;
+                    writeln!(out, "|------|---------|-----|------------|-------------|")?;
+                    for service in &host.services {
+                        writeln!(
+                            out,
+                            "| {} | {} | {} | {} | {} |",
+                            service_port(service),
+                            escape_markdown(&service_product(service)),
+                            escape_markdown(&service.cpe.join(", ")),
+                            escape_markdown(service.http_title.as_deref().unwrap_or_default()),
+                            escape_markdown(service.tls_subject.as_deref().unwrap_or_default())
+                        )?;
+                    }
+                    writeln!(out)?;
+                }
+            }
+        }
+
+        let inventory = report.inventory;
+        writeln!(out, "---")?;
+        writeln!(out)?;
+        writeln!(out, "## Final Report")?;
+        writeln!(out)?;
+        writeln!(out, "### Hosts")?;
+        writeln!(out)?;
+        for (hostname, host) in &inventory.hosts {
+            writeln!(
+                out,
+                "* {} ({})",
+                escape_markdown(hostname),
+                escape_markdown(&join(&host.sources))
+            )?;
+            for (ip, linked_by) in &host.ips {
+                writeln!(out, "  * {} ({})", ip, escape_markdown(&join(linked_by)))?;
+                for (port, service) in ports(inventory, ip) {
+                    writeln!(
+                        out,
+                        "    * {} {} ({})",
+                        port,
+                        escape_markdown(&join(&service.names)),
+                        escape_markdown(&join(&service.sources))
+                    )?;
+                }
+            }
+        }
+        let unattributed = inventory.unattributed_ips();
+        if !unattributed.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "### Unattributed IPs")?;
+            writeln!(out)?;
+            for ip in &unattributed {
+                writeln!(
+                    out,
+                    "* {} ({})",
+                    ip,
+                    escape_markdown(&join(&inventory.ips[ip].sources))
+                )?;
+                for (port, service) in ports(inventory, ip) {
+                    writeln!(
+                        out,
+                        "  * {} {} ({})",
+                        port,
+                        escape_markdown(&join(&service.names)),
+                        escape_markdown(&join(&service.sources))
+                    )?;
+                }
+            }
+        }
+        writeln!(out)?;
+        writeln!(out, "### Emails")?;
+        writeln!(out)?;
+        for email in inventory.emails.keys() {
+            writeln!(out, "* {}", escape_markdown(email))?;
+        }
+        writeln!(out)?;
+        writeln!(out, "### Subdomains")?;
+        writeln!(out)?;
+        for subdomain in inventory.subdomains() {
+            writeln!(out, "* {}", escape_markdown(subdomain))?;
+        }
+
+        Ok(out)
+    }
+}
+
+pub struct XmlReporter;
+
+impl Reporter for XmlReporter {
+    fn extension(&self) -> &'static str {
+        "xml"
+    }
+
+    fn render(&self, report: &Report) -> Result<String, Box<dyn Error>> {
+        let mut out = String::new();
+        writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(out, r#"<report domain="{}">"#, escape_xml(report.domain))?;
+
+        for result in report.results {
+            writeln!(
+                out,
+                r#"  <results source="{}">"#,
+                escape_xml(&result.source)
+            )?;
+            for finding in &result.findings {
+                write_xml_finding(&mut out, finding)?;
+            }
+            writeln!(out, "  </results>")?;
+        }
+
+        for (source, hosts) in host_reports(report.results) {
+            writeln!(out, r#"  <host_report source="{}">"#, escape_xml(source))?;
+            for (ip, host) in &hosts {
+                writeln!(out, r#"    <host ip="{}">"#, ip)?;
+                for hostname in &host.hostnames {
+                    writeln!(out, "      <hostname>{}</hostname>", escape_xml(hostname))?;
+                }
+                if let Some(org) = &host.org {
+                    writeln!(
+                        out,
+                        "      <organization>{}</organization>",
+                        escape_xml(org)
+                    )?;
+                }
+                if let Some(os) = &host.os {
+                    writeln!(
+                        out,
+                        "      <operating_system>{}</operating_system>",
+                        escape_xml(os)
+                    )?;
+                }
+                for cve in &host.vulns {
+                    writeln!(
+                        out,
+                        "      <vulnerability>{}</vulnerability>",
+                        escape_xml(cve)
+                    )?;
+                }
+                for service in &host.services {
+                    writeln!(
+                        out,
+                        r#"      <service port="{}" transport="{}">"#,
+                        service.port,
+                        escape_xml(service.transport.as_deref().unwrap_or_default())
+                    )?;
+                    let fields = [
+                        ("product", &service.product),
+                        ("version", &service.version),
+                        ("http_title", &service.http_title),
+                        ("tls_subject", &service.tls_subject),
+                    ];
+                    for (tag, value) in fields {
+                        if let Some(value) = value {
+                            writeln!(out, "        <{}>{}</{}>", tag, escape_xml(value), tag)?;
+                        }
+                    }
+                    for cpe in &service.cpe {
+                        writeln!(out, "        <cpe>{}</cpe>", escape_xml(cpe))?;
+                    }
+                    writeln!(out, "      </service>")?;
+                }
+                writeln!(out, "    </host>")?;
+            }
+            writeln!(out, "  </host_report>")?;
+        }
+
+        let inventory = report.inventory;
+        writeln!(out, "  <final_report>")?;
+        writeln!(out, "    <hosts>")?;
+        for (hostname, host) in &inventory.hosts {
+            writeln!(
+                out,
+                r#"      <host name="{}" sources="{}">"#,
+                escape_xml(hostname),
+                escape_xml(&join(&host.sources))
+            )?;
+            for (ip, linked_by) in &host.ips {
+                writeln!(
+                    out,
+                    r#"        <ip address="{}" sources="{}">"#,
+                    ip,
+                    escape_xml(&join(linked_by))
+                )?;
+                for (port, service) in ports(inventory, ip) {
+                    writeln!(
+                        out,
+                        r#"          <port number="{}" services="{}" sources="{}"/>"#,
+                        port,
+                        escape_xml(&join(&service.names)),
+                        escape_xml(&join(&service.sources))
+                    )?;
+                }
+                writeln!(out, "        </ip>")?;
+            }
+            writeln!(out, "      </host>")?;
+        }
+        writeln!(out, "    </hosts>")?;
+        writeln!(out, "    <emails>")?;
+        for email in inventory.emails.keys() {
+            writeln!(out, "      <email>{}</email>", escape_xml(email))?;
+        }
+        writeln!(out, "    </emails>")?;
+        writeln!(out, "    <subdomains>")?;
+        for subdomain in inventory.subdomains() {
+            writeln!(
+                out,
+                "      <subdomain>{}</subdomain>",
+                escape_xml(subdomain)
+            )?;
+        }
+        writeln!(out, "    </subdomains>")?;
+        writeln!(out, "  </final_report>")?;
+        writeln!(out, "</report>")?;
+
+        Ok(out)
+    }
+}
+
+/// Writes a finding as an element named after its type, with one child element per field.
+fn write_xml_finding(out: &mut String, finding: &Finding) -> Result<(), Box<dyn Error>> {
+    let value = serde_json::to_value(finding)?;
+    let fields = value.as_object().ok_or("finding is not an object")?;
+    let tag = fields
+        .get("type")
+        .and_then(|v| v.as_str())
+        .unwrap_or("finding");
+
+    writeln!(out, "    <{}>", tag)?;
+    for (key, field) in fields {
+        let text = match field {
+            serde_json::Value::Null => continue,
+            serde_json::Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        if key != "type" {
+            writeln!(out, "      <{}>{}</{}>", key, escape_xml(&text), key)?;
+        }
+    }
+    writeln!(out, "    </{}>", tag)?;
+    Ok(())
+}
+
+pub struct HtmlReporter;
+
+impl Reporter for HtmlReporter {
+    fn extension(&self) -> &'static str {
+        "html"
+    }
+
+    fn render(&self, report: &Report) -> Result<String, Box<dyn Error>> {
+        let mut out = String::new();
+        let domain = escape_html(report.domain);
+        writeln!(out, "<!DOCTYPE html>")?;
+        writeln!(
+            out,
+            "<html><head><meta charset=\"utf-8\"><title>GasMasK report for {}</title></head><body>",
+            domain
+        )?;
+        writeln!(out, "<h2>GasMasK report for {}</h2>", domain)?;
+
+        for result in report.results {
+            writeln!(out, "<h3>{} results</h3>", escape_html(&result.source))?;
+            writeln!(out, "<ul>")?;
+            for finding in listed(result) {
+                writeln!(out, "<li>{}</li>", escape_html(&finding.to_string()))?;
+            }
+            writeln!(out, "</ul>")?;
+            let facets = facets(result);
+            if !facets.is_empty() {
+                writeln!(out, "<table>")?;
+                writeln!(out, "<tr><th>Facet</th><th>Value</th><th>Count</th></tr>")?;
+                for (facet, value, count) in facets {
+                    writeln!(
+                        out,
+                        "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                        escape_html(facet),
+                        escape_html(value),
+                        count
+                    )?;
+                }
+                writeln!(out, "</table>")?;
+            }
+        }
+
+        for (source, hosts) in host_reports(report.results) {
+            writeln!(out, "<h3>{} Report</h3>", escape_html(source))?;
+            for (ip, host) in &hosts {
+                writeln!(out, "<h4>{}</h4>", ip)?;
+                writeln!(out, "<ul>")?;
+                writeln!(
+                    out,
+                    "<li>Hostnames: {}</li>",
+                    escape_html(&host.hostnames.join(", "))
+                )?;
+                writeln!(
+                    out,
+                    "<li>Organization: {}</li>",
+                    escape_html(host.org.as_deref().unwrap_or("n/a"))
+                )?;
+                writeln!(
+                    out,
+                    "<li>Operating System: {}</li>",
+                    escape_html(host.os.as_deref().unwrap_or("n/a"))
+                )?;
+                writeln!(
+                    out,
+                    "<li>Vulnerabilities: {}</li>",
+                    escape_html(&host.vulns.join(", "))
+                )?;
+                writeln!(out, "</ul>")?;
+                if !host.services.is_empty() {
+                    writeln!(out, "<table>")?;
+                    writeln!(
+                        out,
+                        "<tr><th>Port</th><th>Product</th><th>CPE</th><th>HTTP Title</th><th>TLS Subject</th></tr>"
+                    )?;
+                    for service in &host.services {
+                        writeln!(
+                            out,
+                            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                            service_port(service),
+                            escape_html(&service_product(service)),
+                            escape_html(&service.cpe.join(", ")),
+                            escape_html(service.http_title.as_deref().unwrap_or_default()),
+                            escape_html(service.tls_subject.as_deref().unwrap_or_default())
+                        )?;
+                    }
+                    writeln!(out, "</table>")?;
+                }
+            }
+        }
+
+        let inventory = report.inventory;
+        writeln!(out, "<h3>Final Report</h3>")?;
+        writeln!(out, "<h4>Hosts</h4>")?;
+        writeln!(out, "<table>")?;
+        writeln!(
+            out,
+            "<tr><th>Host</th><th>IP</th><th>Ports</th><th>Sources</th></tr>"
+        )?;
+        for (hostname, host) in &inventory.hosts {
+            if host.ips.is_empty() {
+                writeln!(
+                    out,
+                    "<tr><td>{}</td><td></td><td></td><td>{}</td></tr>",
+                    escape_html(hostname),
+                    escape_html(&join(&host.sources))
+                )?;
+            }
+            for (ip, linked_by) in &host.ips {
+                let open_ports = ports(inventory, ip)
+                    .map(|(port, _)| port.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                writeln!(
+                    out,
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    escape_html(hostname),
+                    ip,
+                    open_ports,
+                    escape_html(&join(linked_by))
+                )?;
+            }
+        }
+        writeln!(out, "</table>")?;
+        writeln!(out, "<h4>Emails</h4>\n<ul>")?;
+        for email in inventory.emails.keys() {
+            writeln!(out, "<li>{}</li>", escape_html(email))?;
+        }
+        writeln!(out, "</ul>")?;
+        writeln!(out, "<h4>Subdomains</h4>\n<ul>")?;
+        for subdomain in inventory.subdomains() {
+            writeln!(out, "<li>{}</li>", escape_html(subdomain))?;
+        }
+        writeln!(out, "</ul>")?;
+        writeln!(out, "</body></html>")?;
+
+        Ok(out)
+    }
+}
+
+pub struct JsonReporter;
+
+impl Reporter for JsonReporter {
+    fn extension(&self) -> &'static str {
+        "json"
+    }
+
+    fn render(&self, report: &Report) -> Result<String, Box<dyn Error>> {
+        Ok(serde_json::to_string_pretty(&serde_json::json!({
+            "domain": report.domain,
+            "results": report.results,
+            "inventory": report.inventory,
+        }))?)
+    }
+}
+
+/// Per-IP host details of every result that carries them under `hosts` in its
+/// metadata, by source, like gasmask.py's `ShodanReport`.
+pub fn host_reports(results: &[ModuleResult]) -> Vec<(&str, BTreeMap<IpAddr, HostDetails>)> {
+    results
+        .iter()
+        .filter_map(|result| {
+            let hosts = result.metadata.as_ref()?.get("hosts")?.clone();
+            let hosts = serde_json::from_value::<BTreeMap<IpAddr, HostDetails>>(hosts).ok()?;
+            (!hosts.is_empty()).then_some((result.source.as_str(), hosts))
+        })
+        .collect()
+}
+
+/// Writes the merged hosts, unattributed IPs, emails and subdomains of `inventory` as plain text.
+pub fn write_final_report(out: &mut String, inventory: &Inventory) -> fmt::Result {
+    writeln!(out)?;
+    writeln!(out, "Hosts:")?;
+    for (hostname, host) in &inventory.hosts {
+        writeln!(out, "{} [{}]", hostname, join(&host.sources))?;
+        for (ip, linked_by) in &host.ips {
+            writeln!(out, "  {} [{}]", ip, join(linked_by))?;
+            for (port, service) in ports(inventory, ip) {
+                writeln!(
+                    out,
+                    "    {} {} [{}]",
+                    port,
+                    join(&service.names),
+                    join(&service.sources)
+                )?;
+            }
+        }
+    }
+    let unattributed = inventory.unattributed_ips();
+    if !unattributed.is_empty() {
+        writeln!(out)?;
+        writeln!(out, "Unattributed IPs:")?;
+        for ip in &unattributed {
+            writeln!(out, "{} [{}]", ip, join(&inventory.ips[ip].sources))?;
+            for (port, service) in ports(inventory, ip) {
+                writeln!(
+                    out,
+                    "  {} {} [{}]",
+                    port,
+                    join(&service.names),
+                    join(&service.sources)
+                )?;
+            }
+        }
+    }
+    writeln!(out)?;
+    writeln!(out, "Emails:")?;
+    for (email, found_by) in &inventory.emails {
+        writeln!(out, "{} [{}]", email, join(found_by))?;
+    }
+    writeln!(out)?;
+    writeln!(out, "Subdomains:")?;
+    for subdomain in inventory.subdomains() {
+        writeln!(out, "{}", subdomain)?;
+    }
+    Ok(())
+}
+
+/// Writes host details as plain text, one block per IP.
+pub fn write_host_report(out: &mut String, hosts: &BTreeMap<IpAddr, HostDetails>) -> fmt::Result {
+    for (ip, host) in hosts {
+        writeln!(out)?;
+        writeln!(out, "IP: {}", ip)?;
+        writeln!(out, "-------------------")?;
+        writeln!(out, "Hostnames: {}", host.hostnames.join(","))?;
+        writeln!(
+            out,
+            "Organization: {}",
+            host.org.as_deref().unwrap_or("n/a")
+        )?;
+        writeln!(
+            out,
+            "Operating System: {}",
+            host.os.as_deref().unwrap_or("n/a")
+        )?;
+        if !host.vulns.is_empty() {
+            writeln!(out, "Vulnerabilities: {}", host.vulns.join(", "))?;
+        }
+        for service in &host.services {
+            writeln!(
+                out,
+                "Port: {} {}",
+                service_port(service),
+                service_product(service)
+            )?;
+            if !service.cpe.is_empty() {
+                writeln!(out, "  CPE: {}", service.cpe.join(", "))?;
+            }
+            if let Some(title) = &service.http_title {
+                writeln!(out, "  HTTP Title: {}", title)?;
+            }
+            if let Some(subject) = &service.tls_subject {
+                writeln!(out, "  TLS Subject: {}", subject)?;
+            }
+        }
+    }
+    writeln!(out)
+}
+
+/// `443/tcp` style port of a service.
+fn service_port(service: &ServiceBanner) -> String {
+    match &service.transport {
+        Some(transport) => format!("{}/{}", service.port, transport),
+        None => service.port.to_string(),
+    }
+}
+
+/// Product and version of a service, e.g. `nginx 1.18.0`.
+fn service_product(service: &ServiceBanner) -> String {
+    [&service.product, &service.version]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Findings of a result other than facet counts, which are rendered as a table.
+pub fn listed(result: &ModuleResult) -> impl Iterator<Item = &Finding> {
+    result
+        .findings
+        .iter()
+        .filter(|finding| !matches!(finding, Finding::Facet { .. }))
+}
+
+/// Facet counts of a result as (facet, value, count) rows.
+pub fn facets(result: &ModuleResult) -> Vec<(&str, &str, u64)> {
+    result
+        .findings
+        .iter()
+        .filter_map(|finding| match finding {
+            Finding::Facet {
+                facet,
+                value,
+                count,
+            } => Some((facet.as_str(), value.as_str(), *count)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn join(values: &Sources) -> String {
+    values.iter().cloned().collect::<Vec<String>>().join(", ")
+}
+
+fn ports<'a>(
+    inventory: &'a Inventory,
+    ip: &std::net::IpAddr,
+) -> impl Iterator<Item = (&'a u16, &'a crate::correlation::Service)> {
+    inventory
+        .ips
+        .get(ip)
+        .into_iter()
+        .flat_map(|address| address.ports.iter())
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than whitespace are not allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn escape_markdown(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        if matches!(
+            c,
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|'
+        ) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_markup_in_every_format() {
+        let text = r#"<a href="x">Tom & Jerry</a> | ok"#;
+
+        assert_eq!(
+            escape_xml(text),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&lt;/a&gt; | ok"
+        );
+        assert_eq!(
+            escape_html(text),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&lt;/a&gt; | ok"
+        );
+        assert_eq!(
+            escape_markdown(text),
+            r#"\<a href="x"\>Tom & Jerry\</a\> \| ok"#
+        );
+        assert_eq!(escape_xml("bell\u{7}"), "bell");
+    }
+}
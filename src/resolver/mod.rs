@@ -0,0 +1,114 @@
+use crate::config::{Config, DnsProtocol};
+use std::error::Error;
+use std::net::{IpAddr, SocketAddr, ToSocketAddrs};
+use std::time::Duration;
+use trust_dns_resolver::config::*;
+use trust_dns_resolver::TokioAsyncResolver;
+
+/// Builds the DNS resolver shared by every module from the `--server` and
+/// `--dns-*` options. Without `--server` the resolver falls back to Google DNS.
+pub fn build_resolver(config: &Config) -> Result<TokioAsyncResolver, Box<dyn Error>> {
+    let mut opts = ResolverOpts::default();
+    opts.timeout = Duration::from_secs(config.dns_timeout);
+    opts.attempts = config.dns_retries;
+
+    if config.dns_servers.is_empty() {
+        return Ok(TokioAsyncResolver::tokio(ResolverConfig::default(), opts));
+    }
+
+    let tls_dns_name = match config.dns_protocol {
+        DnsProtocol::Tls | DnsProtocol::Https => Some(
+            config
+                .dns_tls_name
+                .clone()
+                .ok_or("--dns-tls-name is required for DNS-over-TLS and DNS-over-HTTPS")?,
+        ),
+        DnsProtocol::Udp | DnsProtocol::Tcp => None,
+    };
+
+    let mut resolver_config = ResolverConfig::new();
+    for server in &config.dns_servers {
+        for socket_addr in parse_server(server, config.dns_protocol.default_port())? {
+            resolver_config.add_name_server(NameServerConfig {
+                socket_addr,
+                protocol: config.dns_protocol.into(),
+                tls_dns_name: tls_dns_name.clone(),
+                trust_negative_responses: true,
+                tls_config: None,
+                bind_addr: None,
+            });
+        }
+    }
+
+    Ok(TokioAsyncResolver::tokio(resolver_config, opts))
+}
+
+/// Accepts `ip`, `ip:port`, `[ipv6]:port`, `hostname` or `hostname:port`.
+fn parse_server(server: &str, default_port: u16) -> Result<Vec<SocketAddr>, Box<dyn Error>> {
+    let server = server.trim();
+    if let Ok(socket_addr) = server.parse::<SocketAddr>() {
+        return Ok(vec![socket_addr]);
+    }
+    if let Ok(ip) = server.trim_matches(|c| c == '[' || c == ']').parse::<IpAddr>() {
+        return Ok(vec![SocketAddr::new(ip, default_port)]);
+    }
+
+    let addrs: Vec<SocketAddr> = if server.contains(':') {
+        server.to_socket_addrs()?.collect()
+    } else {
+        (server, default_port).to_socket_addrs()?.collect()
+    };
+    if addrs.is_empty() {
+        return Err(format!("Could not resolve DNS server {}", server).into());
+    }
+    Ok(addrs)
+}
+
+impl From<DnsProtocol> for Protocol {
+    fn from(protocol: DnsProtocol) -> Self {
+        match protocol {
+            DnsProtocol::Udp => Protocol::Udp,
+            DnsProtocol::Tcp => Protocol::Tcp,
+            DnsProtocol::Tls => Protocol::Tls,
+            DnsProtocol::Https => Protocol::Https,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn addr(addr: &str) -> SocketAddr {
+        addr.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_servers_with_the_protocol_default_port() {
+        let dot = DnsProtocol::Tls.default_port();
+        let doh = DnsProtocol::Https.default_port();
+
+        assert_eq!(parse_server("9.9.9.9", dot).unwrap(), [addr("9.9.9.9:853")]);
+        assert_eq!(parse_server(" 1.1.1.1 ", doh).unwrap(), [addr("1.1.1.1:443")]);
+        assert_eq!(parse_server("1.1.1.1:5353", doh).unwrap(), [addr("1.1.1.1:5353")]);
+        assert_eq!(parse_server("2001:db8::53", 53).unwrap(), [addr("[2001:db8::53]:53")]);
+        assert_eq!(parse_server("[2001:db8::53]", dot).unwrap(), [addr("[2001:db8::53]:853")]);
+        assert_eq!(parse_server("[2001:db8::53]:8853", dot).unwrap(), [addr("[2001:db8::53]:8853")]);
+    }
+
+    #[test]
+    fn resolves_hostnames() {
+        let addrs = parse_server("localhost", DnsProtocol::Tls.default_port()).unwrap();
+        assert!(addrs.iter().all(|addr| addr.ip().is_loopback() && addr.port() == 853));
+
+        let addrs = parse_server("localhost:5353", 53).unwrap();
+        assert!(addrs.iter().all(|addr| addr.ip().is_loopback() && addr.port() == 5353));
+    }
+
+    #[test]
+    fn rejects_invalid_servers() {
+        for server in ["192.0.2.1:dns", "[2001:db8::53]:70000", "localhost:"] {
+            assert!(parse_server(server, 53).is_err(), "{}", server);
+        }
+    }
+}
@@ -1,6 +1,10 @@
+use regex::{Captures, Regex};
+use sha1::{Digest, Sha1};
+use std::collections::HashMap;
 use std::error::Error;
 use std::fs;
 use std::path::Path;
+use std::sync::{LazyLock, Mutex};
 
 pub fn ensure_directory(path: &str) -> Result<(), Box<dyn Error>> {
     let path = Path::new(path);
@@ -53,4 +57,144 @@ pub fn truncate_string(s: &str, max_len: usize) -> String {
     } else {
         format!("{}...", &s[..max_len - 3])
     }
-} 
\ No newline at end of file
+} 
+
+pub const DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/91.0.4472.124 Safari/537.36";
+
+/// Subdomain labels tried by the vhosts module and by brute force when no `--wordlist` is given.
+pub const COMMON_SUBDOMAINS: &[&str] = &[
+    "www", "mail", "remote", "blog", "webmail", "server", "ns1", "ns2", "smtp", "secure",
+    "vpn", "m", "shop", "ftp", "mail2", "test", "portal", "ns", "ww1", "host", "support",
+    "dev", "web", "bbs", "ww42", "mx", "email", "cloud", "1", "mail1", "2", "forum", "owa",
+    "www2", "gw", "admin", "store", "mx1", "cdn", "api", "exchange", "app", "gov", "2tty",
+    "vps", "govyty", "hgfgdf", "we", "media", "ssl",
+];
+
+// Patterns used by `prepare_text`, compiled on first use
+static HIGHLIGHT_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)</?(em|b|strong|wbr|span)\b[^>]*>").unwrap());
+static ENTITY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"&(#[0-9]+|#[xX][0-9a-fA-F]+|[a-zA-Z]+);").unwrap());
+static ENCODED_SEPARATOR: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)%2f|%3a").unwrap());
+static PERCENT_ESCAPE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"%[0-9A-Fa-f]{2}").unwrap());
+static MAILTO: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)mailto:").unwrap());
+static OBFUSCATED_AT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\s*[\[\(\{<]\s*(at|@)\s*[\]\)\}>]\s*").unwrap());
+static OBFUSCATED_DOT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?i)\s*[\[\(\{<]\s*(dot|\.)\s*[\]\)\}>]\s*").unwrap());
+
+/// Email and hostname patterns of the domains seen so far, compiled once each.
+static DOMAIN_PATTERNS: LazyLock<Mutex<HashMap<String, Regex>>> = LazyLock::new(Default::default);
+
+/// Turns a result page into plain text for extraction: removes the highlighting
+/// tags engines wrap around matches, decodes HTML entities and URL-encoding and
+/// rewrites obfuscated addresses like `user [at] example [dot] com`.
+pub fn prepare_text(html: &str) -> String {
+    let text = replace_all(html, &HIGHLIGHT_TAG, |_| String::new());
+    let text = decode_entities(&text);
+    // Path and scheme separators become spaces so hostnames do not run into URLs
+    let text = replace_all(&text, &ENCODED_SEPARATOR, |_| " ".to_string());
+    let text = replace_all(&text, &PERCENT_ESCAPE, |escape| {
+        match u8::from_str_radix(&escape[1..], 16) {
+            Ok(byte) if byte.is_ascii_graphic() => char::from(byte).to_string(),
+            _ => " ".to_string(),
+        }
+    });
+    let text = replace_all(&text, &MAILTO, |_| " ".to_string());
+    let text = replace_all(&text, &OBFUSCATED_AT, |_| "@".to_string());
+    replace_all(&text, &OBFUSCATED_DOT, |_| ".".to_string())
+}
+
+fn decode_entities(text: &str) -> String {
+    replace_all(text, &ENTITY, |entity| {
+        let name = &entity[1..entity.len() - 1];
+        let decoded = match name {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            "commat" => Some('@'),
+            "period" => Some('.'),
+            _ => {
+                let code = match name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                    None => name.strip_prefix('#').and_then(|decimal| decimal.parse().ok()),
+                };
+                code.and_then(char::from_u32)
+            }
+        };
+        decoded.map_or_else(|| entity.to_string(), String::from)
+    })
+}
+
+fn replace_all(text: &str, re: &Regex, replacement: impl Fn(&str) -> String) -> String {
+    re.replace_all(text, |caps: &Captures| replacement(&caps[0]))
+        .into_owned()
+}
+
+pub fn get_emails(text: &str, domain: &str) -> Vec<String> {
+    let pattern = format!(
+        r"[a-zA-Z0-9.\-_+#~!$&',;=:]+@(?:[a-zA-Z0-9-]+\.)*{}\b",
+        regex::escape(domain)
+    );
+    collect_matches(&prepare_text(text), &pattern)
+}
+
+pub fn get_hostnames(text: &str, domain: &str) -> Vec<String> {
+    let pattern = format!(r"(?:[a-zA-Z0-9-]+\.)+{}\b", regex::escape(domain));
+    collect_matches(&prepare_text(text), &pattern)
+}
+
+fn collect_matches(text: &str, pattern: &str) -> Vec<String> {
+    let mut patterns = DOMAIN_PATTERNS.lock().unwrap();
+    let re = match patterns.get(pattern) {
+        Some(re) => re,
+        None => match Regex::new(pattern) {
+            Ok(re) => patterns.entry(pattern.to_string()).or_insert(re),
+            Err(_) => return Vec::new(),
+        },
+    };
+    let mut matches: Vec<String> = re
+        .find_iter(text)
+        .map(|m| m.as_str().to_lowercase())
+        .collect();
+    matches.sort();
+    matches.dedup();
+    matches
+}
+
+/// Hex-encoded SHA-1 digest, as expected by Netcraft's JS verification cookie.
+pub fn sha1_hex(data: &[u8]) -> String {
+    hex::encode(Sha1::digest(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_encoded_and_obfuscated_addresses() {
+        let page = concat!(
+            "<li>Contact <b>jane.doe</b>@<em>example.com</em></li>",
+            "<a href=\"/url?q=https%3A%2F%2Fdev.example.com%2Fteam\">team</a>",
+            "<p>sales&#64;example&#46;com, ops&commat;mail.example.com</p>",
+            "<p>write to john [at] example [dot] com or support (at) example (dot) com</p>",
+            "<p>mailto:hr%40example.com and someone@other.org</p>",
+            "<p>spoofed by user@notexample.com via foo.badexample.com</p>",
+        );
+
+        assert_eq!(
+            get_emails(page, "example.com"),
+            vec![
+                "hr@example.com",
+                "jane.doe@example.com",
+                "john@example.com",
+                "ops@mail.example.com",
+                "sales@example.com",
+                "support@example.com",
+            ]
+        );
+        assert_eq!(
+            get_hostnames(page, "example.com"),
+            vec!["dev.example.com", "mail.example.com"]
+        );
+    }
+}